@@ -0,0 +1,508 @@
+use crate::error::ChartError;
+use plotters::coord::types::RangedCoordf64;
+use plotters::coord::Shift;
+use plotters::prelude::*;
+use plotters::style::text_anchor::{HPos, Pos, VPos};
+use serde::Deserialize;
+use std::ops::Range;
+use std::str::FromStr;
+
+/// Rounds `x` to a "nice" number: 1, 2, 5 or 10 times a power of ten.
+///
+/// With `round` the closest nice number is returned, otherwise the smallest
+/// nice number not below `x`.
+pub fn nice_number(x: f64, round: bool) -> f64 {
+    if x <= 0.0 || !x.is_finite() {
+        return 1.0;
+    }
+    let exponent = x.log10().floor();
+    let fraction = x / 10f64.powf(exponent);
+    let nice = if round {
+        if fraction < 1.5 {
+            1.0
+        } else if fraction < 3.0 {
+            2.0
+        } else if fraction < 7.0 {
+            5.0
+        } else {
+            10.0
+        }
+    } else if fraction <= 1.0 {
+        1.0
+    } else if fraction <= 2.0 {
+        2.0
+    } else if fraction <= 5.0 {
+        5.0
+    } else {
+        10.0
+    };
+    nice * 10f64.powf(exponent)
+}
+
+/// How an axis range is derived from the data it shows.
+///
+/// `min` and `max` pin either end exactly; the other end is computed from
+/// the data, padded by `padding` (a fraction of the data span) and, with
+/// `nice`, widened to a multiple of a 1/2/5 tick step.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AxisScale {
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+    pub padding: f64,
+    pub include_zero: bool,
+    pub nice: bool,
+    /// Approximate number of ticks used to pick the nice step.
+    pub ticks: usize,
+}
+
+impl Default for AxisScale {
+    fn default() -> Self {
+        AxisScale {
+            min: None,
+            max: None,
+            padding: 0.05,
+            include_zero: false,
+            nice: true,
+            ticks: 10,
+        }
+    }
+}
+
+impl AxisScale {
+    /// A scale that always starts at zero, as bars and histograms need.
+    pub fn from_zero() -> Self {
+        AxisScale {
+            include_zero: true,
+            ..AxisScale::default()
+        }
+    }
+
+    /// A scale pinned to `range`.
+    pub fn fixed(range: Range<f64>) -> Self {
+        AxisScale {
+            min: Some(range.start),
+            max: Some(range.end),
+            ..AxisScale::default()
+        }
+    }
+
+    /// The tick step a nice range over `span` uses.
+    pub fn step(&self, span: f64) -> f64 {
+        let ticks = self.ticks.max(2) as f64;
+        nice_number(nice_number(span, false) / (ticks - 1.0), true)
+    }
+
+    /// Computes the axis range for `values`, ignoring non-finite values.
+    pub fn fit<I: IntoIterator<Item = f64>>(&self, values: I) -> Range<f64> {
+        let (mut lo, mut hi) = values
+            .into_iter()
+            .filter(|v| v.is_finite())
+            .fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), v| (lo.min(v), hi.max(v)));
+        if lo > hi {
+            // No data: fall back to the unit range
+            lo = 0.0;
+            hi = 1.0;
+        }
+        if self.include_zero {
+            lo = lo.min(0.0);
+            hi = hi.max(0.0);
+        }
+        if lo == hi {
+            // A single distinct value gets a range around it
+            if lo == 0.0 {
+                hi = 1.0;
+            } else {
+                let half = lo.abs() / 2.0;
+                lo -= half;
+                hi += half;
+            }
+        }
+
+        // Padding never pushes an end across zero, so counts stay non-negative
+        let pad = (hi - lo) * self.padding;
+        lo = if lo >= 0.0 { (lo - pad).max(0.0) } else { lo - pad };
+        hi = if hi <= 0.0 { (hi + pad).min(0.0) } else { hi + pad };
+
+        if self.nice {
+            let step = self.step(hi - lo);
+            lo = (lo / step).floor() * step;
+            hi = (hi / step).ceil() * step;
+        }
+
+        let start = self.min.unwrap_or(lo);
+        let end = self.max.unwrap_or(hi);
+        if start < end {
+            start..end
+        } else {
+            start..start + 1.0
+        }
+    }
+}
+
+/// A 2D chart with `f64` coordinates on both axes, as the builders draw.
+pub type Chart2d<'a, 'b> =
+    ChartContext<'a, BitMapBackend<'b>, Cartesian2d<RangedCoordf64, RangedCoordf64>>;
+
+/// Draws x-axis tick labels at exact positions, with a light grid line at
+/// each, for axes whose ticks are not evenly spaced numbers (such as month
+/// boundaries). The chart mesh should be drawn with `x_labels(0)` first.
+pub fn draw_x_ticks(
+    root: &DrawingArea<BitMapBackend, Shift>,
+    chart: &mut Chart2d,
+    ticks: &[(f64, String)],
+) -> Result<(), ChartError> {
+    let x_range = chart.x_range();
+    let y_range = chart.y_range();
+    let style = TextStyle::from(("sans-serif", 15)).pos(Pos::new(HPos::Center, VPos::Top));
+
+    for (x, label) in ticks {
+        if *x < x_range.start || *x > x_range.end {
+            continue;
+        }
+        chart.draw_series(std::iter::once(PathElement::new(
+            vec![(*x, y_range.start), (*x, y_range.end)],
+            BLACK.mix(0.15),
+        )))?;
+        let (px, py) = chart.backend_coord(&(*x, y_range.start));
+        root.draw(&Text::new(label.clone(), (px, py + 8), style.clone()))?;
+    }
+
+    Ok(())
+}
+
+/// Draws y-axis labels at exact positions, right-aligned against the axis,
+/// for category axes running down the chart. The chart mesh should be drawn
+/// with `y_labels(0)` first.
+pub fn draw_y_ticks(
+    root: &DrawingArea<BitMapBackend, Shift>,
+    chart: &mut Chart2d,
+    ticks: &[(f64, String)],
+) -> Result<(), ChartError> {
+    let x_range = chart.x_range();
+    let y_range = chart.y_range();
+    let style = TextStyle::from(("sans-serif", 15)).pos(Pos::new(HPos::Right, VPos::Center));
+
+    for (y, label) in ticks {
+        if *y < y_range.start || *y > y_range.end {
+            continue;
+        }
+        let (px, py) = chart.backend_coord(&(x_range.start, *y));
+        root.draw(&Text::new(label.clone(), (px - 8, py), style.clone()))?;
+    }
+
+    Ok(())
+}
+
+/// How category names are fitted under an x-axis.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum TickLayout {
+    /// The first of horizontal, wrapped, 45° and 90° that fits.
+    #[default]
+    Auto,
+    Horizontal,
+    /// Words broken over up to three lines.
+    Wrap,
+    Rotate45,
+    Rotate90,
+}
+
+impl FromStr for TickLayout {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "auto" => Ok(TickLayout::Auto),
+            "horizontal" => Ok(TickLayout::Horizontal),
+            "wrap" => Ok(TickLayout::Wrap),
+            "rotate45" | "45" => Ok(TickLayout::Rotate45),
+            "rotate90" | "90" => Ok(TickLayout::Rotate90),
+            _ => Err(format!(
+                "invalid tick layout '{}', expected auto, horizontal, wrap, rotate45 or rotate90",
+                s
+            )),
+        }
+    }
+}
+
+const TICK_FONT_SIZE: u32 = 15;
+// Minimum pixels between neighbouring labels.
+const TICK_GAP: u32 = 6;
+const MAX_WRAP_LINES: usize = 3;
+
+/// One label per category, laid out to fit the space between category
+/// centers as measured with the label font.
+///
+/// When even 90° labels would overlap, only every `step`-th label is drawn.
+pub struct CategoryTicks {
+    layout: TickLayout,
+    step: usize,
+    lines: Vec<Vec<String>>,
+    line_height: u32,
+    /// Pixels the labels need below the axis.
+    pub height: u32,
+}
+
+// Greedily breaks `label` into lines no wider than `width`.
+fn wrap_label(
+    root: &DrawingArea<BitMapBackend, Shift>,
+    label: &str,
+    width: u32,
+    style: &TextStyle,
+) -> Result<Vec<String>, ChartError> {
+    let mut lines: Vec<String> = Vec::new();
+    for word in label.split_whitespace() {
+        if let Some(last) = lines.last_mut() {
+            let joined = format!("{} {}", last, word);
+            if root.estimate_text_size(&joined, style)?.0 <= width {
+                *last = joined;
+                continue;
+            }
+        }
+        lines.push(word.to_string());
+    }
+    Ok(lines)
+}
+
+impl CategoryTicks {
+    /// Plans labels whose centers lie `slot` pixels apart. `max_labels`
+    /// caps how many are drawn, thinning them evenly.
+    pub fn plan(
+        root: &DrawingArea<BitMapBackend, Shift>,
+        labels: &[String],
+        slot: f64,
+        layout: TickLayout,
+        max_labels: usize,
+    ) -> Result<Self, ChartError> {
+        let style = TextStyle::from(("sans-serif", TICK_FONT_SIZE));
+        let line_height = root.estimate_text_size("Xg", &style)?.1;
+        let mut max_width = 0;
+        for label in labels {
+            max_width = max_width.max(root.estimate_text_size(label, &style)?.0);
+        }
+
+        let room = (slot - TICK_GAP as f64).max(1.0) as u32;
+        // Perpendicular distance between neighbouring 45° labels
+        let diagonal_room = slot / std::f64::consts::SQRT_2;
+        let rotated_fits = |room: f64| room >= (line_height + 2) as f64;
+
+        let mut wrapped = Vec::with_capacity(labels.len());
+        for label in labels {
+            wrapped.push(wrap_label(root, label, room, &style)?);
+        }
+        let mut wrap_fits = true;
+        for lines in &wrapped {
+            for line in lines {
+                wrap_fits &= root.estimate_text_size(line, &style)?.0 <= room;
+            }
+            wrap_fits &= lines.len() <= MAX_WRAP_LINES;
+        }
+
+        let layout = match layout {
+            TickLayout::Auto if max_width <= room => TickLayout::Horizontal,
+            TickLayout::Auto if wrap_fits => TickLayout::Wrap,
+            TickLayout::Auto if rotated_fits(diagonal_room) => TickLayout::Rotate45,
+            TickLayout::Auto => TickLayout::Rotate90,
+            layout => layout,
+        };
+
+        // Thin labels that would still overlap
+        let needed = match layout {
+            TickLayout::Horizontal => (max_width + TICK_GAP) as f64,
+            TickLayout::Wrap => {
+                let mut widest = 0;
+                for line in wrapped.iter().flatten() {
+                    widest = widest.max(root.estimate_text_size(line, &style)?.0);
+                }
+                (widest + TICK_GAP) as f64
+            }
+            TickLayout::Rotate45 => (line_height + 2) as f64 * std::f64::consts::SQRT_2,
+            _ => (line_height + 2) as f64,
+        };
+        let mut step = (needed / slot.max(1.0)).ceil().max(1.0) as usize;
+        if max_labels > 0 {
+            step = step.max(labels.len().div_ceil(max_labels));
+        }
+
+        let lines = match layout {
+            TickLayout::Wrap => wrapped,
+            _ => labels.iter().map(|label| vec![label.clone()]).collect(),
+        };
+        let longest_lines = lines.iter().map(|lines| lines.len()).max().unwrap_or(1) as u32;
+        let height = match layout {
+            TickLayout::Wrap => longest_lines * line_height,
+            TickLayout::Rotate45 => {
+                ((max_width + line_height) as f64 / std::f64::consts::SQRT_2).ceil() as u32
+            }
+            TickLayout::Rotate90 => max_width,
+            _ => line_height,
+        } + 8;
+
+        Ok(CategoryTicks { layout, step, lines, line_height, height })
+    }
+
+    /// Draws label `i` below `centers[i]` (x data coordinates). The chart
+    /// mesh should be drawn with `x_labels(0)` first.
+    pub fn draw(
+        &self,
+        root: &DrawingArea<BitMapBackend, Shift>,
+        chart: &Chart2d,
+        centers: &[f64],
+    ) -> Result<(), ChartError> {
+        let y = chart.y_range().start;
+        let style = TextStyle::from(("sans-serif", TICK_FONT_SIZE));
+
+        for (i, (lines, &x)) in self.lines.iter().zip(centers).enumerate() {
+            if i % self.step != 0 {
+                continue;
+            }
+            let (px, py) = chart.backend_coord(&(x, y));
+            let top = py + 8;
+            match self.layout {
+                TickLayout::Rotate45 => draw_rotated(root, &lines[0], &style, (px, top), 45.0)?,
+                TickLayout::Rotate90 => draw_rotated(root, &lines[0], &style, (px, top), 90.0)?,
+                _ => {
+                    let centered = style.pos(Pos::new(HPos::Center, VPos::Top));
+                    for (row, line) in lines.iter().enumerate() {
+                        let line_top = top + (row as u32 * self.line_height) as i32;
+                        root.draw(&Text::new(line.clone(), (px, line_top), centered.clone()))?;
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+// Draws `text` rotated counter-clockwise by `degrees`, ending at `anchor`.
+// plotters only rotates text by right angles, so the text is rendered into
+// an offscreen bitmap and copied pixel by pixel.
+fn draw_rotated(
+    root: &DrawingArea<BitMapBackend, Shift>,
+    text: &str,
+    style: &TextStyle,
+    anchor: (i32, i32),
+    degrees: f64,
+) -> Result<(), ChartError> {
+    const PAD: u32 = 2;
+    let (text_width, text_height) = root.estimate_text_size(text, style)?;
+    let (width, height) = (text_width + 2 * PAD, text_height + 2 * PAD);
+    let mut buffer = vec![255u8; (width * height * 3) as usize];
+    {
+        let area = BitMapBackend::with_buffer(&mut buffer, (width, height)).into_drawing_area();
+        let style = style.pos(Pos::new(HPos::Left, VPos::Top));
+        area.draw(&Text::new(text, (PAD as i32, PAD as i32), &style))?;
+        area.present()?;
+    }
+
+    // Text runs along `along` and its glyphs' downward side faces `across`
+    let angle = degrees.to_radians();
+    let along = (angle.cos(), -angle.sin());
+    let across = (angle.sin(), angle.cos());
+    // The end of the text, vertically centered, sits on the anchor
+    let origin = ((width - PAD) as f64, height as f64 / 2.0);
+
+    let corners = [(0.0, 0.0), (width as f64, 0.0), (0.0, height as f64), (width as f64, height as f64)];
+    let project = |(u, v): (f64, f64)| {
+        let (du, dv) = (u - origin.0, v - origin.1);
+        (
+            anchor.0 as f64 + du * along.0 + dv * across.0,
+            anchor.1 as f64 + du * along.1 + dv * across.1,
+        )
+    };
+    let (mut x0, mut y0, mut x1, mut y1) = (f64::MAX, f64::MAX, f64::MIN, f64::MIN);
+    for corner in corners {
+        let (x, y) = project(corner);
+        x0 = x0.min(x);
+        y0 = y0.min(y);
+        x1 = x1.max(x);
+        y1 = y1.max(y);
+    }
+
+    for y in y0.floor() as i32..=y1.ceil() as i32 {
+        for x in x0.floor() as i32..=x1.ceil() as i32 {
+            let (dx, dy) = (x as f64 - anchor.0 as f64, y as f64 - anchor.1 as f64);
+            let u = origin.0 + dx * along.0 + dy * along.1;
+            let v = origin.1 + dx * across.0 + dy * across.1;
+            if u < 0.0 || v < 0.0 || u >= width as f64 || v >= height as f64 {
+                continue;
+            }
+            let index = ((v as u32 * width + u as u32) * 3) as usize;
+            let ink = 255 - buffer[index..index + 3].iter().map(|&c| c as u32).sum::<u32>() / 3;
+            if ink > 0 {
+                let (r, g, b) = style.color.rgb;
+                root.draw_pixel((x, y), &RGBColor(r, g, b).mix(ink as f64 / 255.0))?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!((actual - expected).abs() < 1e-9, "{} != {}", actual, expected);
+    }
+
+    fn assert_range(actual: Range<f64>, start: f64, end: f64) {
+        assert_close(actual.start, start);
+        assert_close(actual.end, end);
+    }
+
+    #[test]
+    fn nice_number_rounds_to_one_two_five() {
+        assert_close(nice_number(1.4, true), 1.0);
+        assert_close(nice_number(2.9, true), 2.0);
+        assert_close(nice_number(6.9, true), 5.0);
+        assert_close(nice_number(0.8, true), 1.0);
+        assert_close(nice_number(3.2, false), 5.0);
+        assert_close(nice_number(12.0, false), 20.0);
+        assert_close(nice_number(25_000_000.0, false), 50_000_000.0);
+    }
+
+    #[test]
+    fn nice_number_of_nothing_is_one() {
+        assert_close(nice_number(0.0, false), 1.0);
+        assert_close(nice_number(-3.0, true), 1.0);
+        assert_close(nice_number(f64::NAN, true), 1.0);
+    }
+
+    #[test]
+    fn fit_pads_and_widens_to_nice_steps() {
+        assert_range(AxisScale::default().fit([3.0, 97.0]), 0.0, 120.0);
+        assert_range(AxisScale::from_zero().fit([10.0, 20.0]), 0.0, 25.0);
+    }
+
+    #[test]
+    fn fit_ignores_non_finite_values() {
+        assert_range(AxisScale::from_zero().fit([10.0, f64::NAN, 20.0, f64::INFINITY]), 0.0, 25.0);
+    }
+
+    #[test]
+    fn fit_keeps_pinned_ends() {
+        assert_range(AxisScale::fixed(2.0..8.0).fit([0.0, 100.0]), 2.0, 8.0);
+        let scale = AxisScale { min: Some(0.0), ..AxisScale::default() };
+        assert_range(scale.fit([50.0, 100.0]), 0.0, 110.0);
+    }
+
+    #[test]
+    fn fit_without_padding_or_nice_steps() {
+        let scale = AxisScale { padding: 0.0, nice: false, ..AxisScale::default() };
+        assert_range(scale.fit([-10.0, -2.0]), -10.0, -2.0);
+        // A single value is centered in a range half its size either way
+        assert_range(scale.fit([5.0]), 2.5, 7.5);
+        assert_range(scale.fit(std::iter::empty()), 0.0, 1.0);
+    }
+
+    #[test]
+    fn padding_never_crosses_zero() {
+        let scale = AxisScale { nice: false, ..AxisScale::default() };
+        assert_range(scale.fit([0.0, 10.0]), 0.0, 10.5);
+        assert_range(scale.fit([-10.0, 0.0]), -10.5, 0.0);
+    }
+}
@@ -0,0 +1,1128 @@
+use crate::axis::{draw_x_ticks, draw_y_ticks, AxisScale, CategoryTicks, Chart2d, TickLayout};
+use crate::error::ChartError;
+use crate::format::{axis_label, NumberFormat, NumberStyle};
+use crate::time::{date_position, date_ticks, Calendar, TimeUnit};
+use chrono::NaiveDate;
+use plotters::coord::Shift;
+use plotters::prelude::*;
+use plotters::style::text_anchor::{HPos, Pos, VPos};
+use serde::Deserialize;
+use std::ops::Range;
+use std::str::FromStr;
+
+/// Which way the bars of a [`BarChart`] point.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Orientation {
+    /// Categories along the x-axis, bars rising from it.
+    #[default]
+    Vertical,
+    /// Categories down the y-axis, bars extending right; suits many or long
+    /// category names.
+    Horizontal,
+}
+
+impl FromStr for Orientation {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "vertical" => Ok(Orientation::Vertical),
+            "horizontal" => Ok(Orientation::Horizontal),
+            _ => Err(format!("invalid orientation '{}', expected vertical or horizontal", s)),
+        }
+    }
+}
+
+/// Where a bar's value label sits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LabelPosition {
+    /// Just beyond the end of the bar.
+    #[default]
+    Outside,
+    /// Just inside the end of the bar.
+    Inside,
+    /// In the middle of the bar.
+    Center,
+}
+
+impl FromStr for LabelPosition {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "outside" => Ok(LabelPosition::Outside),
+            "inside" => Ok(LabelPosition::Inside),
+            "center" => Ok(LabelPosition::Center),
+            _ => Err(format!("invalid label position '{}', expected outside, inside or center", s)),
+        }
+    }
+}
+
+/// Value annotations drawn on each bar, e.g. `73,485` above an attendance
+/// bar. A label placed inside a bar too short (or narrow) to hold it moves
+/// outside instead.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ValueLabels {
+    pub position: LabelPosition,
+    /// How the values are written; grouped digits by default, e.g. `73,485`
+    /// or `5,294.3`.
+    pub format: NumberFormat,
+    pub prefix: String,
+    pub suffix: String,
+    pub font_size: u32,
+}
+
+impl Default for ValueLabels {
+    fn default() -> Self {
+        ValueLabels {
+            position: LabelPosition::default(),
+            format: NumberFormat::new(NumberStyle::Thousands),
+            prefix: String::new(),
+            suffix: String::new(),
+            font_size: 14,
+        }
+    }
+}
+
+// Pixels between a label and the bar end it sits against.
+const LABEL_GAP: i32 = 4;
+
+impl ValueLabels {
+    /// The label text for `value`.
+    pub fn format(&self, value: f64) -> String {
+        format!("{}{}{}", self.prefix, self.format.format(value), self.suffix)
+    }
+
+    // The label text for a percent-stacked segment holding `share` percent
+    // of its bar: written in the label's locale, with one decimal unless
+    // `decimals` is set, and with the prefix and suffix.
+    fn format_share(&self, share: f64) -> String {
+        let format = NumberFormat {
+            style: NumberStyle::Percent,
+            decimals: Some(self.format.decimals.unwrap_or(1)),
+            ..self.format.clone()
+        };
+        format!("{}{}{}", self.prefix, format.format(share), self.suffix)
+    }
+
+    fn style(&self) -> TextStyle<'static> {
+        TextStyle::from(("sans-serif", self.font_size)).pos(Pos::new(HPos::Center, VPos::Center))
+    }
+
+    // Labels the bar spanning `rect` (data coordinates). Bars grow up, or
+    // right when `horizontal`, unless `value` is negative.
+    fn draw(
+        &self,
+        root: &DrawingArea<BitMapBackend, Shift>,
+        chart: &Chart2d,
+        value: f64,
+        rect: [(f64, f64); 2],
+        horizontal: bool,
+    ) -> Result<(), ChartError> {
+        let text = self.format(value);
+        let style = self.style();
+        let (ax, ay) = chart.backend_coord(&rect[0]);
+        let (bx, by) = chart.backend_coord(&rect[1]);
+        let bar = (ax.min(bx), ax.max(bx), ay.min(by), ay.max(by));
+        let (width, height) = root.estimate_text_size(&text, &style)?;
+
+        let point = self.place(bar, (width as i32, height as i32), value, horizontal);
+        root.draw(&Text::new(text, point, style))?;
+        Ok(())
+    }
+
+    // The center of a `width` x `height` label for the bar covering the
+    // `(left, right, top, bottom)` pixels.
+    fn place(
+        &self,
+        bar: (i32, i32, i32, i32),
+        (width, height): (i32, i32),
+        value: f64,
+        horizontal: bool,
+    ) -> (i32, i32) {
+        let (left, right, top, bottom) = bar;
+        let (length, across, extent, cross) = if horizontal {
+            (right - left, bottom - top, width, height)
+        } else {
+            (bottom - top, right - left, height, width)
+        };
+        let fits = length >= extent + 2 * LABEL_GAP && across >= cross;
+        let position = if fits { self.position } else { LabelPosition::Outside };
+
+        let center = ((left + right) / 2, (top + bottom) / 2);
+        let offset = match position {
+            LabelPosition::Outside => LABEL_GAP + extent / 2,
+            LabelPosition::Inside => -(LABEL_GAP + extent / 2),
+            LabelPosition::Center => 0,
+        };
+        match (position, horizontal, value < 0.0) {
+            (LabelPosition::Center, _, _) => center,
+            (_, false, false) => (center.0, top - offset),
+            (_, false, true) => (center.0, bottom + offset),
+            (_, true, false) => (right + offset, center.1),
+            (_, true, true) => (left - offset, center.1),
+        }
+    }
+}
+
+// Canvas height per category of a horizontal bar chart without a set size.
+const ROW_HEIGHT: u32 = 28;
+
+/// A bar chart with one bar per category, or one bar per date on a
+/// continuous time axis.
+///
+/// The `x_*` settings always describe the categories and the `y_*` settings
+/// the values, whichever way the bars point.
+pub struct BarChart {
+    x_data: Vec<String>,
+    y_data: Vec<f64>,
+    orientation: Orientation,
+    dates: Option<Vec<NaiveDate>>,
+    time_unit: Option<TimeUnit>,
+    calendar: Calendar,
+    caption: String,
+    size: Option<(u32, u32)>,
+    x_desc: String,
+    y_desc: String,
+    y_scale: AxisScale,
+    x_labels: usize,
+    x_tick_layout: TickLayout,
+    y_labels: usize,
+    y_format: Option<NumberFormat>,
+    bar_width: f64,
+    colors: Vec<RGBAColor>,
+    value_labels: Option<ValueLabels>,
+}
+
+impl BarChart {
+    pub fn new(x_data: Vec<String>, y_data: Vec<f64>) -> Self {
+        BarChart {
+            x_labels: x_data.len(),
+            x_tick_layout: TickLayout::default(),
+            x_data,
+            y_data,
+            orientation: Orientation::default(),
+            dates: None,
+            time_unit: None,
+            calendar: Calendar::default(),
+            caption: String::new(),
+            size: None,
+            x_desc: String::new(),
+            y_desc: String::new(),
+            y_scale: AxisScale::from_zero(),
+            y_labels: 20,
+            y_format: None,
+            bar_width: 0.6,
+            colors: vec![BLUE.mix(0.7), CYAN.mix(0.7)],
+            value_labels: None,
+        }
+    }
+
+    /// Points the bars up (the default) or to the right. Horizontal bars
+    /// always use the category axis, ignoring `dates`.
+    pub fn orientation(mut self, orientation: Orientation) -> Self {
+        self.orientation = orientation;
+        self
+    }
+
+    /// Centers each bar on a date, one date per category, on a continuous
+    /// time axis instead of evenly spaced categories.
+    pub fn dates(mut self, dates: Vec<NaiveDate>) -> Self {
+        self.dates = Some(dates);
+        self
+    }
+
+    /// Tick spacing of the time axis; picked from the date span by default.
+    pub fn time_unit(mut self, unit: TimeUnit) -> Self {
+        self.time_unit = Some(unit);
+        self
+    }
+
+    /// Calendar the time axis labels years in.
+    pub fn calendar(mut self, calendar: Calendar) -> Self {
+        self.calendar = calendar;
+        self
+    }
+
+    pub fn caption(mut self, caption: &str) -> Self {
+        self.caption = caption.to_string();
+        self
+    }
+
+    /// Canvas size. Defaults to 1200x600, or for horizontal bars to a height
+    /// that fits every category.
+    pub fn size(mut self, width: u32, height: u32) -> Self {
+        self.size = Some((width, height));
+        self
+    }
+
+    pub fn x_desc(mut self, desc: &str) -> Self {
+        self.x_desc = desc.to_string();
+        self
+    }
+
+    pub fn y_desc(mut self, desc: &str) -> Self {
+        self.y_desc = desc.to_string();
+        self
+    }
+
+    /// Pins the y-axis to `range` instead of fitting it to the data.
+    pub fn y_range(mut self, range: Range<f64>) -> Self {
+        self.y_scale = AxisScale::fixed(range);
+        self
+    }
+
+    pub fn y_scale(mut self, scale: AxisScale) -> Self {
+        self.y_scale = scale;
+        self
+    }
+
+    /// Most category labels to show; with fewer than there are categories,
+    /// labels are thinned evenly.
+    pub fn x_labels(mut self, count: usize) -> Self {
+        self.x_labels = count;
+        self
+    }
+
+    /// How category labels are fitted when they are too long to sit side by
+    /// side; chosen from the label widths by default.
+    pub fn x_tick_layout(mut self, layout: TickLayout) -> Self {
+        self.x_tick_layout = layout;
+        self
+    }
+
+    pub fn y_labels(mut self, count: usize) -> Self {
+        self.y_labels = count;
+        self
+    }
+
+    /// Writes the value axis labels in `format`, e.g. with thousands
+    /// separators or SI suffixes.
+    pub fn y_format(mut self, format: NumberFormat) -> Self {
+        self.y_format = Some(format);
+        self
+    }
+
+    /// Width of each bar as a fraction of the category slot.
+    pub fn bar_width(mut self, width: f64) -> Self {
+        self.bar_width = width;
+        self
+    }
+
+    /// Colors cycled through bar by bar.
+    pub fn colors(mut self, colors: Vec<RGBAColor>) -> Self {
+        if !colors.is_empty() {
+            self.colors = colors;
+        }
+        self
+    }
+
+    /// Writes each bar's value on or beside it.
+    pub fn value_labels(mut self, labels: ValueLabels) -> Self {
+        self.value_labels = Some(labels);
+        self
+    }
+
+    pub fn generate_chart(&self, output_path: &str) -> Result<(), ChartError> {
+        if self.x_data.is_empty() {
+            return Err(ChartError::EmptyData("bar chart has no categories".to_string()));
+        }
+        if self.orientation == Orientation::Horizontal {
+            return self.generate_horizontal_chart(output_path);
+        }
+        if let Some(dates) = &self.dates {
+            return self.generate_time_chart(output_path, dates);
+        }
+
+        let size = self.canvas_size();
+        let root = BitMapBackend::new(output_path, size).into_drawing_area();
+        root.fill(&WHITE)?;
+
+        // Lay the labels out for the plot width left between the label areas
+        let count = self.x_data.len();
+        let slot = size.0.saturating_sub(160) as f64 / count.max(1) as f64;
+        let ticks = CategoryTicks::plan(&root, &self.x_data, slot, self.x_tick_layout, self.x_labels)?;
+        let desc_height = if self.x_desc.is_empty() { 0 } else { 30 };
+
+        let mut chart = ChartBuilder::on(&root)
+            .caption(&self.caption, ("sans-serif", 40))
+            .set_label_area_size(LabelAreaPosition::Left, 60)
+            .set_label_area_size(LabelAreaPosition::Right, 60)
+            .set_label_area_size(LabelAreaPosition::Bottom, (ticks.height + desc_height).max(40))
+            .margin(20)
+            .build_cartesian_2d(
+                0.0..count as f64,
+                self.y_scale.fit(self.y_data.iter().cloned()),
+            )?;
+
+        chart
+            .configure_mesh()
+            .x_labels(0)
+            .y_labels(self.y_labels)
+            .y_label_formatter(&|y| axis_label(self.y_format.as_ref(), *y))
+            .x_desc(&self.x_desc)
+            .y_desc(&self.y_desc)
+            .axis_desc_style(TextStyle::from(("sans-serif", 20)))
+            .draw()?;
+
+        let centers: Vec<f64> = (0..count).map(|i| i as f64 + 0.5).collect();
+        ticks.draw(&root, &chart, &centers)?;
+
+        // Each bar is centered in its category slot
+        let margin = (1.0 - self.bar_width) / 2.0;
+        chart.draw_series(self.y_data.iter().enumerate().filter(|(_, y)| y.is_finite()).map(|(i, &y)| {
+            let x = i as f64 + margin;
+            let color = self.colors[i % self.colors.len()];
+            Rectangle::new([(x, 0.0), (x + self.bar_width, y)], color.filled())
+        }))?;
+
+        if let Some(labels) = &self.value_labels {
+            for (i, &y) in self.y_data.iter().enumerate().filter(|(_, y)| y.is_finite()) {
+                let x = i as f64 + margin;
+                labels.draw(&root, &chart, y, [(x, 0.0), (x + self.bar_width, y)], false)?;
+            }
+        }
+
+        root.present()?;
+
+        Ok(())
+    }
+
+    // Bars centered on their dates, as wide as `bar_width` of the closest
+    // spacing between two dates.
+    fn generate_time_chart(&self, output_path: &str, dates: &[NaiveDate]) -> Result<(), ChartError> {
+        let (start, end) = match (dates.iter().min(), dates.iter().max()) {
+            (Some(&start), Some(&end)) => (start, end),
+            _ => return Err(ChartError::EmptyData("no dates to plot".to_string())),
+        };
+
+        let mut positions: Vec<f64> = dates.iter().map(|&date| date_position(date)).collect();
+        positions.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        let spacing = positions
+            .windows(2)
+            .map(|pair| pair[1] - pair[0])
+            .filter(|gap| *gap > 0.0)
+            .fold(f64::INFINITY, f64::min);
+        let spacing = if spacing.is_finite() { spacing } else { 30.0 };
+        let half = spacing * self.bar_width / 2.0;
+
+        let root = BitMapBackend::new(output_path, self.canvas_size()).into_drawing_area();
+        root.fill(&WHITE)?;
+
+        let mut chart = ChartBuilder::on(&root)
+            .caption(&self.caption, ("sans-serif", 40))
+            .set_label_area_size(LabelAreaPosition::Left, 60)
+            .set_label_area_size(LabelAreaPosition::Right, 60)
+            .set_label_area_size(LabelAreaPosition::Bottom, 40)
+            .margin(20)
+            .build_cartesian_2d(
+                date_position(start) - spacing / 2.0..date_position(end) + spacing / 2.0,
+                self.y_scale.fit(self.y_data.iter().cloned()),
+            )?;
+
+        chart
+            .configure_mesh()
+            .x_labels(0)
+            .y_labels(self.y_labels)
+            .y_label_formatter(&|y| axis_label(self.y_format.as_ref(), *y))
+            .x_desc(&self.x_desc)
+            .y_desc(&self.y_desc)
+            .axis_desc_style(TextStyle::from(("sans-serif", 20)))
+            .draw()?;
+
+        let unit = self.time_unit.unwrap_or_else(|| TimeUnit::for_span(start, end));
+        draw_x_ticks(&root, &mut chart, &date_ticks(start, end, unit, self.calendar))?;
+
+        let bars = dates.iter().zip(self.y_data.iter()).enumerate();
+        chart.draw_series(bars.filter(|(_, (_, y))| y.is_finite()).map(|(i, (&date, &y))| {
+            let x = date_position(date);
+            let color = self.colors[i % self.colors.len()];
+            Rectangle::new([(x - half, 0.0), (x + half, y)], color.filled())
+        }))?;
+
+        if let Some(labels) = &self.value_labels {
+            for (&date, &y) in dates.iter().zip(self.y_data.iter()).filter(|(_, y)| y.is_finite()) {
+                let x = date_position(date);
+                labels.draw(&root, &chart, y, [(x - half, 0.0), (x + half, y)], false)?;
+            }
+        }
+
+        root.present()?;
+
+        Ok(())
+    }
+
+    // The canvas size: as set, or one row per category when horizontal.
+    fn canvas_size(&self) -> (u32, u32) {
+        match (self.size, self.orientation) {
+            (Some(size), _) => size,
+            (None, Orientation::Horizontal) => (1200, 140 + ROW_HEIGHT * self.x_data.len().max(1) as u32),
+            (None, Orientation::Vertical) => (1200, 600),
+        }
+    }
+
+    // The bottom of category `i`'s row on a horizontal chart, where the
+    // first category sits at the top.
+    fn row(&self, i: usize) -> f64 {
+        (self.x_data.len() - 1 - i) as f64
+    }
+
+    // Bars `(category, bottom, value)` of a horizontal chart; a `NaN` value
+    // leaves its bar out.
+    fn horizontal_bars(&self) -> Vec<(usize, f64, f64)> {
+        let margin = (1.0 - self.bar_width) / 2.0;
+        self.y_data
+            .iter()
+            .enumerate()
+            .filter(|(_, value)| value.is_finite())
+            .map(|(i, &value)| (i, self.row(i) + margin, value))
+            .collect()
+    }
+
+    // Categories top to bottom in data order, each label in full beside its
+    // bar.
+    fn generate_horizontal_chart(&self, output_path: &str) -> Result<(), ChartError> {
+        let count = self.x_data.len();
+        let root = BitMapBackend::new(output_path, self.canvas_size()).into_drawing_area();
+        root.fill(&WHITE)?;
+
+        // Room for the longest category name
+        let label_style = TextStyle::from(("sans-serif", 15));
+        let mut label_width = 0;
+        for label in &self.x_data {
+            label_width = label_width.max(root.estimate_text_size(label, &label_style)?.0);
+        }
+        let desc_width = if self.x_desc.is_empty() { 0 } else { 30 };
+
+        let mut chart = ChartBuilder::on(&root)
+            .caption(&self.caption, ("sans-serif", 40))
+            .set_label_area_size(LabelAreaPosition::Left, label_width + 20 + desc_width)
+            .set_label_area_size(LabelAreaPosition::Bottom, 40)
+            .margin(20)
+            .build_cartesian_2d(
+                self.y_scale.fit(self.y_data.iter().cloned()),
+                0.0..count as f64,
+            )?;
+
+        chart
+            .configure_mesh()
+            .disable_y_mesh()
+            .x_labels(self.y_labels)
+            .x_label_formatter(&|x| axis_label(self.y_format.as_ref(), *x))
+            .y_labels(0)
+            .x_desc(&self.y_desc)
+            .y_desc(&self.x_desc)
+            .axis_desc_style(TextStyle::from(("sans-serif", 20)))
+            .draw()?;
+
+        let ticks: Vec<(f64, String)> = self
+            .x_data
+            .iter()
+            .enumerate()
+            .map(|(i, label)| (self.row(i) + 0.5, label.clone()))
+            .collect();
+        draw_y_ticks(&root, &mut chart, &ticks)?;
+
+        let bars = self.horizontal_bars();
+        chart.draw_series(bars.iter().map(|&(i, y, value)| {
+            let color = self.colors[i % self.colors.len()];
+            Rectangle::new([(0.0, y), (value, y + self.bar_width)], color.filled())
+        }))?;
+
+        if let Some(labels) = &self.value_labels {
+            for &(_, y, value) in &bars {
+                labels.draw(&root, &chart, value, [(0.0, y), (value, y + self.bar_width)], true)?;
+            }
+        }
+
+        root.present()?;
+
+        Ok(())
+    }
+}
+
+/// How a [`GroupedBarChart`] lays out the groups of a category.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum BarMode {
+    /// One bar per group, side by side.
+    #[default]
+    Grouped,
+    /// One bar per category with a segment per group; positive values stack
+    /// up from zero and negative values down.
+    Stacked,
+    /// Like `Stacked`, with each category scaled to 100% of its total.
+    PercentStacked,
+}
+
+impl FromStr for BarMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "grouped" => Ok(BarMode::Grouped),
+            "stacked" => Ok(BarMode::Stacked),
+            "percent-stacked" | "percent" => Ok(BarMode::PercentStacked),
+            _ => Err(format!(
+                "invalid bar mode '{}', expected grouped, stacked or percent-stacked",
+                s
+            )),
+        }
+    }
+}
+
+/// A bar chart drawing several bars for each category, one per group, side
+/// by side or stacked, with a legend mapping colors to group names.
+///
+/// Any number of groups share each category slot evenly. A `NaN` value (a
+/// category the group has no data for) leaves its bar out.
+pub struct GroupedBarChart {
+    categories: Vec<String>,
+    groups: Vec<(String, Vec<f64>)>,
+    mode: BarMode,
+    value_labels: Option<ValueLabels>,
+    caption: String,
+    size: (u32, u32),
+    x_desc: String,
+    y_desc: String,
+    y_scale: AxisScale,
+    y_labels: usize,
+    y_format: Option<NumberFormat>,
+    group_width: f64,
+    x_tick_layout: TickLayout,
+    colors: Vec<RGBAColor>,
+}
+
+impl GroupedBarChart {
+    pub fn new(categories: Vec<String>) -> Self {
+        GroupedBarChart {
+            categories,
+            groups: Vec::new(),
+            mode: BarMode::default(),
+            value_labels: None,
+            caption: String::new(),
+            size: (1600, 800),
+            x_desc: String::new(),
+            y_desc: String::new(),
+            y_scale: AxisScale::from_zero(),
+            y_labels: 20,
+            y_format: None,
+            group_width: 0.8,
+            x_tick_layout: TickLayout::default(),
+            colors: Vec::new(),
+        }
+    }
+
+    /// Adds a group with one value per category.
+    pub fn group(mut self, name: &str, values: Vec<f64>) -> Self {
+        self.groups.push((name.to_string(), values));
+        self
+    }
+
+    /// Adds every group, e.g. as returned by [`crate::data::read_grouped`].
+    pub fn groups(mut self, groups: Vec<(String, Vec<f64>)>) -> Self {
+        self.groups.extend(groups);
+        self
+    }
+
+    pub fn caption(mut self, caption: &str) -> Self {
+        self.caption = caption.to_string();
+        self
+    }
+
+    pub fn size(mut self, width: u32, height: u32) -> Self {
+        self.size = (width, height);
+        self
+    }
+
+    pub fn x_desc(mut self, desc: &str) -> Self {
+        self.x_desc = desc.to_string();
+        self
+    }
+
+    pub fn y_desc(mut self, desc: &str) -> Self {
+        self.y_desc = desc.to_string();
+        self
+    }
+
+    /// Pins the y-axis to `range` instead of fitting it to the data.
+    pub fn y_range(mut self, range: Range<f64>) -> Self {
+        self.y_scale = AxisScale::fixed(range);
+        self
+    }
+
+    pub fn y_scale(mut self, scale: AxisScale) -> Self {
+        self.y_scale = scale;
+        self
+    }
+
+    pub fn y_labels(mut self, count: usize) -> Self {
+        self.y_labels = count;
+        self
+    }
+
+    /// Writes the y tick labels in `format`, e.g. with thousands
+    /// separators or SI suffixes.
+    pub fn y_format(mut self, format: NumberFormat) -> Self {
+        self.y_format = Some(format);
+        self
+    }
+
+    pub fn mode(mut self, mode: BarMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Writes each bar's value on or beside it. Stacked segments are labeled
+    /// in their center with their value (or share, in percent mode), and
+    /// left unlabeled when too small for the text.
+    pub fn value_labels(mut self, labels: ValueLabels) -> Self {
+        self.value_labels = Some(labels);
+        self
+    }
+
+    /// How category labels are fitted when they are too long to sit side by
+    /// side; chosen from the label widths by default.
+    pub fn x_tick_layout(mut self, layout: TickLayout) -> Self {
+        self.x_tick_layout = layout;
+        self
+    }
+
+    /// Width of each category's group of bars as a fraction of the slot.
+    pub fn group_width(mut self, width: f64) -> Self {
+        self.group_width = width;
+        self
+    }
+
+    /// Colors assigned to groups in order. Groups beyond the list fall back
+    /// to `Palette99`.
+    pub fn colors(mut self, colors: Vec<RGBAColor>) -> Self {
+        self.colors = colors;
+        self
+    }
+
+    fn group_color(&self, index: usize) -> RGBAColor {
+        match self.colors.get(index) {
+            Some(color) => *color,
+            None => Palette99::pick(index).to_rgba(),
+        }
+    }
+
+    // Width of each bar in grouped mode, the groups sharing a category's
+    // slot evenly.
+    fn bar_width(&self) -> f64 {
+        self.group_width / self.groups.len().max(1) as f64
+    }
+
+    // Bars `(group, category, left, value)` of grouped mode; a `NaN` value
+    // leaves its bar out.
+    fn grouped_bars(&self) -> Vec<(usize, usize, f64, f64)> {
+        let padding = (1.0 - self.group_width) / 2.0;
+        let bar_width = self.bar_width();
+        let mut bars = Vec::new();
+        for (group, (_, values)) in self.groups.iter().enumerate() {
+            let offset = padding + group as f64 * bar_width;
+            for (category, &value) in values.iter().enumerate().filter(|(_, y)| y.is_finite()) {
+                bars.push((group, category, category as f64 + offset, value));
+            }
+        }
+        bars
+    }
+
+    // Segments `(group, category, bottom, top)` of the stacked modes.
+    fn segments(&self) -> Vec<(usize, usize, f64, f64)> {
+        let mut segments = Vec::new();
+        for category in 0..self.categories.len() {
+            let values: Vec<f64> = self
+                .groups
+                .iter()
+                .map(|(_, values)| values.get(category).cloned().unwrap_or(f64::NAN))
+                .collect();
+            let scale = match self.mode {
+                BarMode::PercentStacked => {
+                    let total: f64 = values.iter().filter(|v| v.is_finite()).map(|v| v.abs()).sum();
+                    if total > 0.0 {
+                        100.0 / total
+                    } else {
+                        0.0
+                    }
+                }
+                _ => 1.0,
+            };
+
+            let (mut up, mut down) = (0.0, 0.0);
+            for (group, value) in values.iter().enumerate() {
+                if !value.is_finite() || *value == 0.0 {
+                    continue;
+                }
+                let height = value * scale;
+                if height > 0.0 {
+                    segments.push((group, category, up, up + height));
+                    up += height;
+                } else {
+                    segments.push((group, category, down + height, down));
+                    down += height;
+                }
+            }
+        }
+        segments
+    }
+
+    // The value axis: fitted to the bars or the stacks, or the whole
+    // percentage span, below zero too when any value is negative.
+    fn fit_y(&self, segments: &[(usize, usize, f64, f64)]) -> Range<f64> {
+        match self.mode {
+            BarMode::Grouped => self
+                .y_scale
+                .fit(self.groups.iter().flat_map(|(_, values)| values.iter().cloned())),
+            BarMode::Stacked => self
+                .y_scale
+                .fit(segments.iter().flat_map(|&(_, _, bottom, top)| [bottom, top])),
+            BarMode::PercentStacked => {
+                let has_negative = segments.iter().any(|&(_, _, bottom, _)| bottom < 0.0);
+                if has_negative { -100.0..100.0 } else { 0.0..100.0 }
+            }
+        }
+    }
+
+    pub fn generate_chart(&self, output_path: &str) -> Result<(), ChartError> {
+        if self.categories.is_empty() || self.groups.is_empty() {
+            return Err(ChartError::EmptyData("grouped bar chart has no categories or groups".to_string()));
+        }
+        let segments = self.segments();
+        let y_range = self.fit_y(&segments);
+
+        let root = BitMapBackend::new(output_path, self.size).into_drawing_area();
+        root.fill(&WHITE)?;
+
+        // Lay the labels out for the plot width left beside the y labels
+        let count = self.categories.len();
+        let slot = self.size.0.saturating_sub(140) as f64 / count.max(1) as f64;
+        let ticks = CategoryTicks::plan(&root, &self.categories, slot, self.x_tick_layout, 0)?;
+        let desc_height = if self.x_desc.is_empty() { 0 } else { 30 };
+
+        let mut chart = ChartBuilder::on(&root)
+            .caption(&self.caption, ("sans-serif", 40))
+            .x_label_area_size((ticks.height + desc_height).max(60))
+            .y_label_area_size(100)
+            .margin(20)
+            .build_cartesian_2d(0.0..count as f64, y_range)?;
+
+        let percent = self.mode == BarMode::PercentStacked;
+        chart
+            .configure_mesh()
+            .x_labels(0)
+            .y_labels(self.y_labels)
+            .y_label_formatter(&|y| match &self.y_format {
+                None if percent => format!("{}%", y),
+                format => axis_label(format.as_ref(), *y),
+            })
+            .x_desc(&self.x_desc)
+            .y_desc(&self.y_desc)
+            .axis_desc_style(TextStyle::from(("sans-serif", 20)))
+            .draw()?;
+
+        let centers: Vec<f64> = (0..count).map(|i| i as f64 + 0.5).collect();
+        ticks.draw(&root, &chart, &centers)?;
+
+        let padding = (1.0 - self.group_width) / 2.0;
+
+        if self.mode == BarMode::Grouped {
+            let bar_width = self.bar_width();
+            let bars = self.grouped_bars();
+
+            for (index, (name, _)) in self.groups.iter().enumerate() {
+                let color = self.group_color(index);
+                let group_bars = bars.iter().filter(|bar| bar.0 == index);
+
+                chart
+                    .draw_series(group_bars.clone().map(|&(_, _, x, y)| {
+                        Rectangle::new([(x, 0.0), (x + bar_width, y)], color.filled())
+                    }))?
+                    .label(name.as_str())
+                    .legend(move |(x, y)| Rectangle::new([(x, y - 5), (x + 10, y + 5)], color.filled()));
+
+                if let Some(labels) = &self.value_labels {
+                    for &(_, _, x, y) in group_bars {
+                        labels.draw(&root, &chart, y, [(x, 0.0), (x + bar_width, y)], false)?;
+                    }
+                }
+            }
+        } else {
+            for (index, (name, _)) in self.groups.iter().enumerate() {
+                let color = self.group_color(index);
+
+                chart
+                    .draw_series(segments.iter().filter(|segment| segment.0 == index).map(
+                        |&(_, category, bottom, top)| {
+                            let x = category as f64 + padding;
+                            Rectangle::new([(x, bottom), (x + self.group_width, top)], color.filled())
+                        },
+                    ))?
+                    .label(name.as_str())
+                    .legend(move |(x, y)| Rectangle::new([(x, y - 5), (x + 10, y + 5)], color.filled()));
+            }
+
+            if let Some(labels) = &self.value_labels {
+                let style = labels.style();
+                for &(group, category, bottom, top) in &segments {
+                    let text = if percent {
+                        labels.format_share(top - bottom)
+                    } else {
+                        labels.format(self.groups[group].1[category])
+                    };
+                    let x = category as f64 + 0.5;
+                    let (left, y_top) = chart.backend_coord(&(category as f64 + padding, top));
+                    let (right, y_bottom) =
+                        chart.backend_coord(&(category as f64 + padding + self.group_width, bottom));
+                    let (text_width, text_height) =
+                        chart.plotting_area().estimate_text_size(&text, &style)?;
+                    if text_width as i32 > right - left || text_height as i32 > y_bottom - y_top {
+                        continue;
+                    }
+                    chart.draw_series(std::iter::once(Text::new(
+                        text,
+                        (x, (bottom + top) / 2.0),
+                        style.clone(),
+                    )))?;
+                }
+            }
+        }
+
+        chart
+            .configure_series_labels()
+            .background_style(WHITE.mix(0.8))
+            .border_style(BLACK)
+            .label_font(("sans-serif", 20))
+            .draw()?;
+
+        root.present()?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::format::Locale;
+
+    fn labels(position: LabelPosition) -> ValueLabels {
+        ValueLabels { position, ..ValueLabels::default() }
+    }
+
+    #[test]
+    fn formats_labels_with_prefix_and_suffix() {
+        let labels = ValueLabels { prefix: "~".to_string(), suffix: " fans".to_string(), ..ValueLabels::default() };
+        assert_eq!(labels.format(73485.0), "~73,485 fans");
+        assert_eq!(labels.format(-1250.5), "~-1,250.5 fans");
+    }
+
+    #[test]
+    fn formats_percent_shares_like_values() {
+        assert_eq!(ValueLabels::default().format_share(25.0), "25.0%");
+
+        let labels = ValueLabels {
+            format: NumberFormat { decimals: Some(2), locale: Locale::Dot, ..NumberFormat::default() },
+            prefix: "(".to_string(),
+            suffix: ")".to_string(),
+            ..ValueLabels::default()
+        };
+        assert_eq!(labels.format_share(12.5), "(12,50%)");
+    }
+
+    // A 20 px wide bar from y = 50 to y = 250 and a 20 x 10 label
+    const UPRIGHT: (i32, i32, i32, i32) = (100, 120, 50, 250);
+    const TEXT: (i32, i32) = (20, 10);
+
+    #[test]
+    fn places_labels_on_upright_bars() {
+        assert_eq!(labels(LabelPosition::Outside).place(UPRIGHT, TEXT, 5.0, false), (110, 41));
+        assert_eq!(labels(LabelPosition::Inside).place(UPRIGHT, TEXT, 5.0, false), (110, 59));
+        assert_eq!(labels(LabelPosition::Center).place(UPRIGHT, TEXT, 5.0, false), (110, 150));
+    }
+
+    #[test]
+    fn places_labels_below_negative_bars() {
+        assert_eq!(labels(LabelPosition::Outside).place(UPRIGHT, TEXT, -5.0, false), (110, 259));
+        assert_eq!(labels(LabelPosition::Inside).place(UPRIGHT, TEXT, -5.0, false), (110, 241));
+        assert_eq!(labels(LabelPosition::Center).place(UPRIGHT, TEXT, -5.0, false), (110, 150));
+    }
+
+    #[test]
+    fn places_labels_on_horizontal_bars() {
+        let bar = (0, 200, 10, 30);
+        let text = (30, 12);
+        assert_eq!(labels(LabelPosition::Outside).place(bar, text, 5.0, true), (219, 20));
+        assert_eq!(labels(LabelPosition::Inside).place(bar, text, 5.0, true), (181, 20));
+        assert_eq!(labels(LabelPosition::Outside).place(bar, text, -5.0, true), (-19, 20));
+        assert_eq!(labels(LabelPosition::Inside).place(bar, text, -5.0, true), (19, 20));
+    }
+
+    #[test]
+    fn moves_labels_that_do_not_fit_outside() {
+        // Too short to hold the label and its gaps
+        let short = (100, 120, 50, 65);
+        assert_eq!(labels(LabelPosition::Inside).place(short, TEXT, 5.0, false), (110, 41));
+        assert_eq!(labels(LabelPosition::Center).place(short, TEXT, -5.0, false), (110, 74));
+        // Narrower than the label
+        let narrow = (100, 110, 50, 250);
+        assert_eq!(labels(LabelPosition::Inside).place(narrow, TEXT, 5.0, false), (105, 41));
+        let thin = (0, 200, 10, 20);
+        assert_eq!(labels(LabelPosition::Center).place(thin, (30, 12), 5.0, true), (219, 15));
+    }
+
+    fn grouped(groups: &[&[f64]]) -> GroupedBarChart {
+        let categories = (0..groups[0].len()).map(|i| format!("c{}", i)).collect();
+        groups
+            .iter()
+            .enumerate()
+            .fold(GroupedBarChart::new(categories), |chart, (i, values)| {
+                chart.group(&format!("g{}", i), values.to_vec())
+            })
+    }
+
+    fn close(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-9
+    }
+
+    #[test]
+    fn shares_each_slot_between_groups() {
+        let chart = grouped(&[&[1.0, 2.0], &[3.0, 4.0], &[5.0, 6.0]]);
+        let width = 0.8 / 3.0;
+        assert!(close(chart.bar_width(), width));
+
+        let bars = chart.grouped_bars();
+        assert_eq!(bars.len(), 6);
+        for &(group, category, left, value) in &bars {
+            assert!(close(left, category as f64 + 0.1 + group as f64 * width));
+            assert_eq!(value, chart.groups[group].1[category]);
+        }
+        // The bars of a category fill its group width exactly
+        let last = bars.iter().filter(|bar| bar.1 == 1).map(|bar| bar.2).fold(0.0, f64::max);
+        assert!(close(last + width, 1.9));
+    }
+
+    #[test]
+    fn leaves_out_bars_without_a_value() {
+        let chart = grouped(&[&[1.0, f64::NAN], &[f64::NAN, -4.0]]).group_width(1.0);
+        assert_eq!(chart.grouped_bars(), vec![(0, 0, 0.0, 1.0), (1, 1, 1.5, -4.0)]);
+    }
+
+    #[test]
+    fn colors_groups_from_the_palette_after_the_list() {
+        let chart = grouped(&[&[1.0], &[2.0], &[3.0]]).colors(vec![RED.mix(1.0), GREEN.mix(1.0)]);
+        assert_eq!(chart.group_color(0), RED.mix(1.0));
+        assert_eq!(chart.group_color(1), GREEN.mix(1.0));
+        assert_eq!(chart.group_color(2), Palette99::pick(2).to_rgba());
+    }
+
+    #[test]
+    fn rejects_grouped_charts_without_groups() {
+        let chart = GroupedBarChart::new(vec!["a".to_string()]);
+        assert!(matches!(chart.generate_chart("unused.png"), Err(ChartError::EmptyData(_))));
+        let chart = GroupedBarChart::new(Vec::new()).group("g", Vec::new());
+        assert!(matches!(chart.generate_chart("unused.png"), Err(ChartError::EmptyData(_))));
+    }
+
+    #[test]
+    fn stacks_positive_values_up_and_negative_values_down() {
+        let chart =
+            grouped(&[&[1.0, -1.0], &[-2.0, 0.0], &[3.0, f64::NAN], &[-4.0, 5.0]]).mode(BarMode::Stacked);
+        assert_eq!(
+            chart.segments(),
+            vec![
+                (0, 0, 0.0, 1.0),
+                (1, 0, -2.0, 0.0),
+                (2, 0, 1.0, 4.0),
+                (3, 0, -6.0, -2.0),
+                (0, 1, -1.0, 0.0),
+                (3, 1, 0.0, 5.0),
+            ]
+        );
+    }
+
+    #[test]
+    fn fits_stacked_axis_to_stack_ends() {
+        let chart = grouped(&[&[1.0, -1.0], &[-2.0, 2.0], &[3.0, 3.0]])
+            .mode(BarMode::Stacked)
+            .y_scale(AxisScale { nice: false, padding: 0.0, ..AxisScale::default() });
+        assert_eq!(chart.fit_y(&chart.segments()), -2.0..5.0);
+
+        let grouped = grouped(&[&[1.0, -1.0], &[-2.0, 2.0], &[3.0, 3.0]])
+            .y_scale(AxisScale { nice: false, padding: 0.0, ..AxisScale::default() });
+        assert_eq!(grouped.fit_y(&grouped.segments()), -2.0..3.0);
+    }
+
+    #[test]
+    fn scales_percent_stacks_to_shares() {
+        let chart = grouped(&[&[1.0, 0.0], &[3.0, 0.0]]).mode(BarMode::PercentStacked);
+        let segments = chart.segments();
+        assert_eq!(segments, vec![(0, 0, 0.0, 25.0), (1, 0, 25.0, 100.0)]);
+        assert_eq!(chart.fit_y(&segments), 0.0..100.0);
+
+        // Shares are of the absolute total, negative ones stacking down
+        let chart = grouped(&[&[1.0], &[-1.0], &[f64::NAN], &[2.0]]).mode(BarMode::PercentStacked);
+        let segments = chart.segments();
+        assert_eq!(segments, vec![(0, 0, 0.0, 25.0), (1, 0, -25.0, 0.0), (3, 0, 25.0, 75.0)]);
+        assert_eq!(chart.fit_y(&segments), -100.0..100.0);
+    }
+
+    #[test]
+    fn parses_bar_modes() {
+        assert_eq!("percent".parse::<BarMode>(), Ok(BarMode::PercentStacked));
+        assert_eq!(" Stacked".parse::<BarMode>(), Ok(BarMode::Stacked));
+        assert!("overlap".parse::<BarMode>().is_err());
+    }
+
+    fn horizontal(values: &[f64]) -> BarChart {
+        let categories = (0..values.len()).map(|i| format!("City {}", i)).collect();
+        BarChart::new(categories, values.to_vec()).orientation(Orientation::Horizontal)
+    }
+
+    #[test]
+    fn lists_categories_top_down() {
+        let chart = horizontal(&[10.0, f64::NAN, -5.0]).bar_width(0.5);
+        assert_eq!(chart.horizontal_bars(), vec![(0, 2.25, 10.0), (2, 0.25, -5.0)]);
+    }
+
+    #[test]
+    fn sizes_horizontal_canvas_to_the_categories() {
+        assert_eq!(horizontal(&[1.0; 45]).canvas_size(), (1200, 140 + 45 * ROW_HEIGHT));
+        assert_eq!(horizontal(&[]).canvas_size(), (1200, 140 + ROW_HEIGHT));
+        assert_eq!(horizontal(&[1.0; 45]).size(800, 900).canvas_size(), (800, 900));
+        let vertical = BarChart::new(vec!["a".to_string(); 45], vec![1.0; 45]);
+        assert_eq!(vertical.canvas_size(), (1200, 600));
+    }
+
+    #[test]
+    fn renders_horizontal_bars_at_the_derived_height() {
+        let path = std::env::temp_dir().join(format!("chartkit-horizontal-{}.png", std::process::id()));
+        let chart = horizontal(&[73485.0, 22000.0, 15100.0, -300.0])
+            .value_labels(ValueLabels::default())
+            .generate_chart(path.to_str().unwrap());
+        let png = std::fs::read(&path);
+        let _ = std::fs::remove_file(&path);
+
+        chart.unwrap();
+        // Width and height follow the PNG signature and IHDR chunk header
+        let png = png.unwrap();
+        let dimension = |at: usize| u32::from_be_bytes(png[at..at + 4].try_into().unwrap());
+        assert_eq!((dimension(16), dimension(20)), (1200, 140 + 4 * ROW_HEIGHT));
+    }
+
+    #[test]
+    fn parses_orientations() {
+        assert_eq!("Horizontal".parse::<Orientation>(), Ok(Orientation::Horizontal));
+        assert!("sideways".parse::<Orientation>().is_err());
+    }
+
+    #[test]
+    fn parses_label_positions() {
+        assert_eq!(" Inside".parse::<LabelPosition>(), Ok(LabelPosition::Inside));
+        assert!("above".parse::<LabelPosition>().is_err());
+    }
+}
@@ -0,0 +1,263 @@
+use serde::{Deserialize, Deserializer};
+use std::fmt;
+use std::str::FromStr;
+
+// Upper bound on the number of bins, so a tiny width cannot exhaust memory.
+const MAX_BINS: usize = 10_000;
+
+/// How values are grouped into histogram bins.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum BinRule {
+    /// A fixed number of equal-width bins spanning the data.
+    Count(usize),
+    /// Bins of a fixed width, aligned to multiples of the width.
+    Width(f64),
+    /// `ceil(log2(n)) + 1` bins; suits roughly normal data.
+    #[default]
+    Sturges,
+    /// Width `3.49 * stddev * n^(-1/3)`.
+    Scott,
+    /// Width `2 * IQR * n^(-1/3)`; robust to outliers.
+    FreedmanDiaconis,
+}
+
+/// A half-open bin `[start, end)` with the number of values falling in it.
+/// The last bin also includes its `end`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Bin {
+    pub start: f64,
+    pub end: f64,
+    pub count: usize,
+}
+
+impl FromStr for BinRule {
+    type Err = String;
+
+    /// Parses `sturges`, `scott`, `fd` / `freedman-diaconis`, a bin count such
+    /// as `12`, or a bin width such as `width:5`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        match s.to_ascii_lowercase().as_str() {
+            "sturges" => Ok(BinRule::Sturges),
+            "scott" => Ok(BinRule::Scott),
+            "fd" | "freedman-diaconis" => Ok(BinRule::FreedmanDiaconis),
+            other => {
+                if let Some(width) = other.strip_prefix("width:") {
+                    let width = width.trim();
+                    return match width.parse::<f64>() {
+                        Ok(value) if value > 0.0 && value.is_finite() => Ok(BinRule::Width(value)),
+                        _ => Err(format!("invalid bin width '{}', expected a positive number", width)),
+                    };
+                }
+                match other.parse::<usize>() {
+                    Ok(count) if count > 0 => Ok(BinRule::Count(count)),
+                    _ => Err(format!(
+                        "invalid bin rule '{}', expected sturges, scott, fd, a count or width:<w>",
+                        s
+                    )),
+                }
+            }
+        }
+    }
+}
+
+impl fmt::Display for BinRule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BinRule::Count(count) => write!(f, "{}", count),
+            BinRule::Width(width) => write!(f, "width:{}", width),
+            BinRule::Sturges => write!(f, "sturges"),
+            BinRule::Scott => write!(f, "scott"),
+            BinRule::FreedmanDiaconis => write!(f, "fd"),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for BinRule {
+    /// Accepts a bin count (`bins = 12`) or the string forms of `FromStr`.
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Raw {
+            Count(usize),
+            Text(String),
+        }
+
+        match Raw::deserialize(deserializer)? {
+            Raw::Count(count) => BinRule::from_str(&count.to_string()),
+            Raw::Text(text) => BinRule::from_str(&text),
+        }
+        .map_err(serde::de::Error::custom)
+    }
+}
+
+// Linearly interpolated quantile of sorted values.
+fn quantile(sorted: &[f64], q: f64) -> f64 {
+    let pos = q * (sorted.len() - 1) as f64;
+    let lower = pos.floor() as usize;
+    let upper = pos.ceil() as usize;
+    sorted[lower] + (sorted[upper] - sorted[lower]) * (pos - lower as f64)
+}
+
+fn sturges_width(n: usize, span: f64) -> f64 {
+    span / ((n as f64).log2().ceil() + 1.0)
+}
+
+impl BinRule {
+    // Bin width for finite, sorted, non-empty values.
+    fn width(&self, sorted: &[f64]) -> f64 {
+        let n = sorted.len();
+        let span = sorted[n - 1] - sorted[0];
+        let width = match *self {
+            BinRule::Count(count) => span / count.max(1) as f64,
+            BinRule::Width(width) => width,
+            BinRule::Sturges => sturges_width(n, span),
+            BinRule::Scott => {
+                let mean = sorted.iter().sum::<f64>() / n as f64;
+                let variance = sorted.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / n as f64;
+                3.49 * variance.sqrt() * (n as f64).powf(-1.0 / 3.0)
+            }
+            BinRule::FreedmanDiaconis => {
+                let iqr = quantile(sorted, 0.75) - quantile(sorted, 0.25);
+                2.0 * iqr * (n as f64).powf(-1.0 / 3.0)
+            }
+        };
+
+        // Degenerate spreads (e.g. an IQR of zero) fall back to Sturges
+        if width > 0.0 && width.is_finite() {
+            width
+        } else {
+            sturges_width(n, span)
+        }
+    }
+
+    /// Groups `values` into bins, ignoring NaN and infinite values.
+    pub fn bin(&self, values: &[f64]) -> Vec<Bin> {
+        let mut sorted: Vec<f64> = values.iter().cloned().filter(|v| v.is_finite()).collect();
+        if sorted.is_empty() {
+            return Vec::new();
+        }
+        sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+        let min = sorted[0];
+        let max = sorted[sorted.len() - 1];
+        if min == max {
+            // Every value is the same: one bin centered on it
+            return vec![Bin { start: min - 0.5, end: max + 0.5, count: sorted.len() }];
+        }
+
+        let mut width = self.width(&sorted);
+        let start = match self {
+            BinRule::Width(_) => (min / width).floor() * width,
+            _ => min,
+        };
+        // The tolerance keeps rounding error from adding a sliver of a last bin
+        let mut count = (((max - start) / width - 1e-9).ceil() as usize).max(1);
+        if count > MAX_BINS {
+            count = MAX_BINS;
+            width = (max - start) / count as f64;
+        }
+
+        let mut bins: Vec<Bin> = (0..count)
+            .map(|i| Bin {
+                start: start + i as f64 * width,
+                end: start + (i + 1) as f64 * width,
+                count: 0,
+            })
+            .collect();
+        for value in sorted {
+            let index = (((value - start) / width).floor() as usize).min(count - 1);
+            bins[index].count += 1;
+        }
+
+        bins
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn counts(bins: &[Bin]) -> Vec<usize> {
+        bins.iter().map(|bin| bin.count).collect()
+    }
+
+    fn one_to_eight() -> Vec<f64> {
+        (1..=8).map(f64::from).collect()
+    }
+
+    #[test]
+    fn parses_rules() {
+        assert_eq!("12".parse::<BinRule>(), Ok(BinRule::Count(12)));
+        assert_eq!("width:2.5".parse::<BinRule>(), Ok(BinRule::Width(2.5)));
+        assert_eq!(" Sturges ".parse::<BinRule>(), Ok(BinRule::Sturges));
+        assert_eq!("scott".parse::<BinRule>(), Ok(BinRule::Scott));
+        assert_eq!("freedman-diaconis".parse::<BinRule>(), Ok(BinRule::FreedmanDiaconis));
+    }
+
+    #[test]
+    fn rejects_invalid_rules_with_context() {
+        assert_eq!(
+            "width:abc".parse::<BinRule>(),
+            Err("invalid bin width 'abc', expected a positive number".to_string())
+        );
+        assert!("width:-1".parse::<BinRule>().unwrap_err().contains("'-1'"));
+        assert!("0".parse::<BinRule>().unwrap_err().contains("invalid bin rule '0'"));
+        assert!("many".parse::<BinRule>().is_err());
+    }
+
+    #[test]
+    fn count_splits_the_span_evenly() {
+        let values: Vec<f64> = (0..=8).map(f64::from).collect();
+        let bins = BinRule::Count(4).bin(&values);
+        assert_eq!(counts(&bins), vec![2, 2, 2, 3]);
+        assert_eq!((bins[0].start, bins[3].end), (0.0, 8.0));
+    }
+
+    #[test]
+    fn width_aligns_bins_to_multiples() {
+        let bins = BinRule::Width(5.0).bin(&[3.0, 7.0, 12.0]);
+        assert_eq!(counts(&bins), vec![1, 1, 1]);
+        assert_eq!((bins[0].start, bins[2].end), (0.0, 15.0));
+    }
+
+    #[test]
+    fn sturges_uses_log2_bins() {
+        let bins = BinRule::Sturges.bin(&one_to_eight());
+        assert_eq!(counts(&bins), vec![2, 2, 2, 2]);
+        assert!((bins[0].end - bins[0].start - 1.75).abs() < 1e-9);
+    }
+
+    #[test]
+    fn scott_width_follows_the_standard_deviation() {
+        let bins = BinRule::Scott.bin(&one_to_eight());
+        let expected = 3.49 * 5.25f64.sqrt() * 0.5;
+        assert!((bins[0].end - bins[0].start - expected).abs() < 1e-9);
+        assert_eq!(counts(&bins).iter().sum::<usize>(), 8);
+    }
+
+    #[test]
+    fn freedman_diaconis_width_follows_the_iqr() {
+        let bins = BinRule::FreedmanDiaconis.bin(&one_to_eight());
+        assert!((bins[0].end - bins[0].start - 3.5).abs() < 1e-9);
+        assert_eq!(counts(&bins), vec![4, 4]);
+    }
+
+    #[test]
+    fn freedman_diaconis_falls_back_to_sturges_without_spread() {
+        let values = [1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 10.0];
+        assert_eq!(BinRule::FreedmanDiaconis.bin(&values).len(), 4);
+    }
+
+    #[test]
+    fn ignores_non_finite_values() {
+        let bins = BinRule::Count(2).bin(&[f64::NAN, 0.0, 4.0, f64::INFINITY]);
+        assert_eq!(counts(&bins), vec![1, 1]);
+        assert!(BinRule::Sturges.bin(&[f64::NAN]).is_empty());
+    }
+
+    #[test]
+    fn equal_values_share_one_centered_bin() {
+        assert_eq!(BinRule::Scott.bin(&[3.0, 3.0]), vec![Bin { start: 2.5, end: 3.5, count: 2 }]);
+    }
+}
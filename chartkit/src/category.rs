@@ -0,0 +1,179 @@
+use serde::Deserialize;
+use std::cmp::Ordering;
+use std::str::FromStr;
+
+/// The order categories are drawn in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SortOrder {
+    /// The order of the rows in the file.
+    #[default]
+    Original,
+    #[serde(alias = "value")]
+    ValueDesc,
+    ValueAsc,
+    #[serde(alias = "label")]
+    LabelAsc,
+    LabelDesc,
+}
+
+impl FromStr for SortOrder {
+    type Err = String;
+
+    /// Parses `original`, `value` / `value-desc`, `value-asc`, `label` /
+    /// `label-asc` or `label-desc`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "original" => Ok(SortOrder::Original),
+            "value" | "value-desc" => Ok(SortOrder::ValueDesc),
+            "value-asc" => Ok(SortOrder::ValueAsc),
+            "label" | "label-asc" => Ok(SortOrder::LabelAsc),
+            "label-desc" => Ok(SortOrder::LabelDesc),
+            _ => Err(format!(
+                "invalid sort order '{}', expected original, value, value-asc, label or label-desc",
+                s
+            )),
+        }
+    }
+}
+
+/// Sorting and top-N truncation of a chart's categories, e.g.
+///
+/// ```toml
+/// [categories]
+/// sort = "value"
+/// top = 10
+/// other = "Other"
+/// ```
+///
+/// keeps the ten largest categories and sums the rest into one "Other" bar.
+/// Without `other` the remaining categories are dropped. A chart with a time
+/// x-axis cannot use `other`, as the bucket has no date.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CategoryFilter {
+    pub sort: SortOrder,
+    pub top: Option<usize>,
+    pub other: Option<String>,
+}
+
+impl CategoryFilter {
+    /// Sorts, truncates and buckets `labels` with their `values`.
+    ///
+    /// `NaN` values sort last whichever the direction; the "Other" bucket is
+    /// the sum of the finite values cut off.
+    pub fn apply(&self, labels: Vec<String>, values: Vec<f64>) -> (Vec<String>, Vec<f64>) {
+        let mut rows: Vec<(String, f64)> = labels.into_iter().zip(values).collect();
+
+        let by_value = |a: &f64, b: &f64| match (a.is_nan(), b.is_nan()) {
+            (true, true) => Ordering::Equal,
+            (true, false) => Ordering::Greater,
+            (false, true) => Ordering::Less,
+            _ => a.partial_cmp(b).unwrap(),
+        };
+        // Stable sorts keep file order among ties
+        match self.sort {
+            SortOrder::Original => {}
+            SortOrder::ValueAsc => rows.sort_by(|a, b| by_value(&a.1, &b.1)),
+            SortOrder::ValueDesc => rows.sort_by(|a, b| match (a.1.is_nan(), b.1.is_nan()) {
+                (false, false) => by_value(&b.1, &a.1),
+                _ => by_value(&a.1, &b.1),
+            }),
+            SortOrder::LabelAsc => rows.sort_by(|a, b| a.0.cmp(&b.0)),
+            SortOrder::LabelDesc => rows.sort_by(|a, b| b.0.cmp(&a.0)),
+        }
+
+        if let Some(top) = self.top {
+            if top < rows.len() {
+                let rest = rows.split_off(top);
+                if let Some(other) = &self.other {
+                    let total = rest.iter().map(|(_, value)| *value).filter(|v| v.is_finite()).sum();
+                    rows.push((other.clone(), total));
+                }
+            }
+        }
+
+        rows.into_iter().unzip()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn labels(names: &[&str]) -> Vec<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    fn filter(sort: SortOrder, top: Option<usize>, other: Option<&str>) -> CategoryFilter {
+        CategoryFilter { sort, top, other: other.map(str::to_string) }
+    }
+
+    #[test]
+    fn sorts_nan_last_in_both_directions() {
+        let values = vec![2.0, f64::NAN, 5.0, 1.0];
+        let names = labels(&["b", "nan", "e", "a"]);
+
+        let (desc, _) = filter(SortOrder::ValueDesc, None, None).apply(names.clone(), values.clone());
+        assert_eq!(desc, labels(&["e", "b", "a", "nan"]));
+        let (asc, _) = filter(SortOrder::ValueAsc, None, None).apply(names, values);
+        assert_eq!(asc, labels(&["a", "b", "e", "nan"]));
+    }
+
+    #[test]
+    fn keeps_file_order_among_ties() {
+        let names = labels(&["x", "y", "z", "w"]);
+        let values = vec![1.0, 3.0, 1.0, 3.0];
+
+        let (desc, _) = filter(SortOrder::ValueDesc, None, None).apply(names.clone(), values.clone());
+        assert_eq!(desc, labels(&["y", "w", "x", "z"]));
+        let (asc, _) = filter(SortOrder::ValueAsc, None, None).apply(names, values);
+        assert_eq!(asc, labels(&["x", "z", "y", "w"]));
+    }
+
+    #[test]
+    fn sorts_by_label() {
+        let names = labels(&["b", "c", "a"]);
+        let (asc, values) = filter(SortOrder::LabelAsc, None, None).apply(names.clone(), vec![2.0, 3.0, 1.0]);
+        assert_eq!(asc, labels(&["a", "b", "c"]));
+        assert_eq!(values, vec![1.0, 2.0, 3.0]);
+        let (desc, _) = filter(SortOrder::LabelDesc, None, None).apply(names, vec![2.0, 3.0, 1.0]);
+        assert_eq!(desc, labels(&["c", "b", "a"]));
+    }
+
+    #[test]
+    fn sums_cut_categories_into_other() {
+        let names = labels(&["a", "b", "c", "d", "e"]);
+        let values = vec![1.0, 5.0, f64::NAN, 4.0, 2.0];
+        let (names, values) = filter(SortOrder::ValueDesc, Some(2), Some("Other")).apply(names, values);
+        assert_eq!(names, labels(&["b", "d", "Other"]));
+        // NaN is cut last and left out of the sum
+        assert_eq!(values, vec![5.0, 4.0, 3.0]);
+    }
+
+    #[test]
+    fn drops_cut_categories_without_other() {
+        let names = labels(&["a", "b", "c"]);
+        let (names, values) = filter(SortOrder::Original, Some(2), None).apply(names, vec![1.0, 2.0, 3.0]);
+        assert_eq!(names, labels(&["a", "b"]));
+        assert_eq!(values, vec![1.0, 2.0]);
+    }
+
+    #[test]
+    fn adds_no_other_when_nothing_is_cut() {
+        let names = labels(&["a", "b", "c"]);
+        for top in [3, 10] {
+            let (cut, values) =
+                filter(SortOrder::Original, Some(top), Some("Other")).apply(names.clone(), vec![1.0, 2.0, 3.0]);
+            assert_eq!(cut, names);
+            assert_eq!(values, vec![1.0, 2.0, 3.0]);
+        }
+    }
+
+    #[test]
+    fn parses_sort_orders() {
+        assert_eq!("value".parse::<SortOrder>(), Ok(SortOrder::ValueDesc));
+        assert_eq!(" Label-Desc ".parse::<SortOrder>(), Ok(SortOrder::LabelDesc));
+        assert!("size".parse::<SortOrder>().is_err());
+    }
+}
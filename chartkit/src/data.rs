@@ -0,0 +1,1003 @@
+use crate::dialect::Dialect;
+use crate::encoding::{open_text, Encoding};
+use crate::error::ChartError;
+use crate::table::{for_each_record, Melt, Pivot, Table};
+use serde::Deserialize;
+use std::cell::OnceCell;
+use std::convert::Infallible;
+use std::fmt;
+use std::fs;
+use std::io::{BufRead, BufReader};
+use std::mem;
+use std::str::FromStr;
+
+// Files larger than this are streamed rather than held in memory.
+const STREAM_BYTES: u64 = 64 * 1024 * 1024;
+
+// Read buffer of a streamed file, also the sample its delimiter is detected from.
+const STREAM_BUFFER: usize = 256 * 1024;
+
+/// Text labels paired with one or more numeric columns of the same length.
+pub type LabeledColumns = (Vec<String>, Vec<Vec<f64>>);
+
+/// Categories paired with named groups holding one value per category.
+pub type GroupedColumns = (Vec<String>, Vec<(String, Vec<f64>)>);
+
+/// A reference to a CSV column, either by header name or by position.
+///
+/// Names are matched against headers with surrounding whitespace trimmed, so
+/// `"Player"` finds the `" Player"` header. A name that matches no header but
+/// is a number is used as a zero-based index instead.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(untagged)]
+pub enum Column {
+    Index(usize),
+    Name(String),
+}
+
+impl Column {
+    /// Returns the position of this column within `headers`.
+    pub fn resolve(&self, headers: &[String]) -> Result<usize, ChartError> {
+        let index = match self {
+            Column::Index(index) => Some(*index).filter(|&i| i < headers.len()),
+            Column::Name(name) => {
+                let name = name.trim();
+                headers
+                    .iter()
+                    .position(|header| header.trim() == name)
+                    .or_else(|| name.parse::<usize>().ok().filter(|&i| i < headers.len()))
+            }
+        };
+
+        index.ok_or_else(|| ChartError::ColumnNotFound {
+            column: self.to_string(),
+            available: headers.iter().map(|header| header.trim().to_string()).collect(),
+        })
+    }
+}
+
+impl fmt::Display for Column {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Column::Index(index) => write!(f, "#{}", index),
+            Column::Name(name) => write!(f, "'{}'", name),
+        }
+    }
+}
+
+impl FromStr for Column {
+    type Err = Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Column::Name(s.to_string()))
+    }
+}
+
+impl From<&str> for Column {
+    fn from(name: &str) -> Self {
+        Column::Name(name.to_string())
+    }
+}
+
+impl From<usize> for Column {
+    fn from(index: usize) -> Self {
+        Column::Index(index)
+    }
+}
+
+/// Parses a numeric cell as written in spreadsheets and reports, e.g.
+/// `£1.92`, `20.40%`, `1,234,567`, `(1,250)` or `1.234,5 €`, returning the
+/// value with the unit stripped from around it (`£`, `%`, `€`, `kg`, ...).
+///
+/// Parenthesized amounts are negative. Spaces and apostrophes always group
+/// digits. With both `,` and `.` present the last one is the decimal mark;
+/// on its own, `,` groups digits when followed by exactly three digits (or
+/// repeated) and is a decimal mark otherwise, while `.` is a decimal mark
+/// unless repeated. Percentages stay in percentage points.
+///
+/// Only a currency (`$`, `US$`, `THB`) may precede the number; after it may
+/// also come `%` or a short unit such as `kg`, `km/h` or `°C`. Other text,
+/// as in `Jan 2020` or `Room 101`, and non-finite values such as `inf` or
+/// `NaN` are not numbers.
+pub fn parse_value(text: &str) -> Option<(f64, Option<String>)> {
+    let mut text = text.trim();
+    if let Ok(value) = text.parse::<f64>() {
+        return value.is_finite().then_some((value, None));
+    }
+    let mut negative = false;
+    if let Some(inner) = text.strip_prefix('(').and_then(|t| t.strip_suffix(')')) {
+        negative = true;
+        text = inner.trim();
+    }
+
+    // The number runs from the first to the last digit; a sign or decimal
+    // mark right before the first digit belongs to it
+    let first = text.find(|c: char| c.is_ascii_digit())?;
+    let last = text.rfind(|c: char| c.is_ascii_digit())? + 1;
+    let mut start = first;
+    if text[..start].ends_with(['.', ',']) {
+        start -= 1;
+    }
+    let (mut prefix, number, mut suffix) = (&text[..start], &text[start..last], &text[last..]);
+    for sign in ['-', '\u{2212}', '+'] {
+        if let Some(rest) = prefix.trim_end().strip_suffix(sign).or_else(|| prefix.strip_prefix(sign)) {
+            negative ^= sign != '+';
+            prefix = rest;
+            break;
+        }
+    }
+    // Parentheses inside a currency symbol, as in `$(1,250)`
+    if let (Some(rest), Some(after)) = (
+        prefix.trim_end().strip_suffix('('),
+        suffix.trim_start().strip_prefix(')'),
+    ) {
+        negative = !negative;
+        prefix = rest;
+        suffix = after;
+    }
+
+    let (prefix, suffix) = (prefix.trim(), suffix.trim());
+    let unit = match (prefix.is_empty(), suffix.is_empty()) {
+        (true, true) => None,
+        (false, true) if is_currency(prefix) => Some(prefix.to_string()),
+        (true, false) if is_unit(suffix) => Some(suffix.to_string()),
+        // A symbol on both sides is not a single unit
+        _ => return None,
+    };
+
+    let digits: String = number
+        .chars()
+        .filter(|c| !matches!(c, ' ' | '\'' | '\u{a0}' | '\u{202f}'))
+        .collect();
+    let digits = match (digits.rfind(','), digits.rfind('.')) {
+        (Some(comma), Some(dot)) if comma > dot => digits.replace('.', "").replace(',', "."),
+        (Some(_), Some(_)) => digits.replace(',', ""),
+        (Some(comma), None) => {
+            let grouped = digits.matches(',').count() > 1 || digits.len() - comma == 4 && !digits.starts_with('0');
+            if grouped { digits.replace(',', "") } else { digits.replace(',', ".") }
+        }
+        (None, Some(_)) if digits.matches('.').count() > 1 => digits.replace('.', ""),
+        _ => digits,
+    };
+
+    let value: f64 = digits.parse().ok().filter(|value: &f64| value.is_finite())?;
+    Some((if negative { -value } else { value }, unit))
+}
+
+// Currency symbols a value may be written with.
+const CURRENCY_SYMBOLS: &str = "$£€¥฿₹₩₽¢₫₺₪₱₦₴₡";
+
+// ISO codes of the currencies most often written before an amount.
+const CURRENCY_CODES: &[&str] = &[
+    "USD", "EUR", "GBP", "THB", "JPY", "CNY", "HKD", "SGD", "AUD", "NZD", "CAD", "CHF", "INR",
+    "KRW", "MYR", "IDR", "PHP", "VND", "SEK", "NOK", "DKK", "RUB", "BRL", "MXN", "ZAR",
+];
+
+// Longest unit read after a number, in characters, e.g. `km/h`.
+const MAX_UNIT_CHARS: usize = 5;
+
+// Whether `unit` is a currency symbol, possibly with a country prefix as in
+// `US$` or `HK$`, or a currency code.
+fn is_currency(unit: &str) -> bool {
+    let symbols = unit.chars().filter(|&c| CURRENCY_SYMBOLS.contains(c)).count();
+    match symbols {
+        0 => CURRENCY_CODES.contains(&unit),
+        1 => {
+            unit.chars().count() <= 3
+                && unit.chars().all(|c| CURRENCY_SYMBOLS.contains(c) || c.is_ascii_uppercase())
+        }
+        _ => false,
+    }
+}
+
+// Whether `unit` may follow a number: a currency, a percent sign or a short
+// token such as `kg`, `km/h`, `°C` or `m²`.
+fn is_unit(unit: &str) -> bool {
+    is_currency(unit)
+        || unit == "%"
+        || unit == "‰"
+        || unit.chars().count() <= MAX_UNIT_CHARS
+            && unit.chars().any(char::is_alphabetic)
+            && unit.chars().all(|c| c.is_alphabetic() || "°/²³".contains(c))
+}
+
+// Parses a cell as a number, dropping its unit.
+pub(crate) fn parse_number(text: &str) -> Option<f64> {
+    parse_value(text).map(|(value, _)| value)
+}
+
+/// The unit stripped from the values of a column, e.g. `£` or `%`: that of
+/// the first value written with one.
+pub fn column_unit(path: &str, col: impl Into<Column>) -> Result<Option<String>, ChartError> {
+    Loader::new(path).unit(col)
+}
+
+/// Reads the header row of a CSV file with surrounding whitespace trimmed.
+pub fn read_headers(path: &str) -> Result<Vec<String>, ChartError> {
+    Loader::new(path).headers()
+}
+
+/// Resolves each column against the header row of a CSV file.
+pub fn resolve_columns(path: &str, cols: &[Column]) -> Result<Vec<usize>, ChartError> {
+    let headers = read_headers(path)?;
+    cols.iter().map(|col| col.resolve(&headers)).collect()
+}
+
+// The requested cells of a record, `None` where the record is too short,
+// and the line it starts on.
+struct Row<'r> {
+    line: u64,
+    cells: Vec<Option<&'r str>>,
+}
+
+/// What a loader does with a value that is empty, missing from a short
+/// record or not a number.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MissingPolicy {
+    /// Drop the record.
+    #[default]
+    Skip,
+    /// Read the value as 0.
+    Zero,
+    /// Keep the record with a `NaN` value, which charts leave out: no bar,
+    /// no point, a break in a line.
+    Gap,
+    /// Stop loading with an error naming the cell.
+    Fail,
+}
+
+impl FromStr for MissingPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "skip" => Ok(MissingPolicy::Skip),
+            "zero" => Ok(MissingPolicy::Zero),
+            "gap" => Ok(MissingPolicy::Gap),
+            "fail" => Ok(MissingPolicy::Fail),
+            _ => Err(format!("invalid missing-value policy '{}', expected skip, zero, gap or fail", s)),
+        }
+    }
+}
+
+/// A cell a loader could not read.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RejectedCell {
+    pub file: String,
+    /// Line of the record in the file; the header is line 1.
+    pub line: u64,
+    pub column: String,
+    /// The cell as written; empty for a cell missing from a short record.
+    pub text: String,
+}
+
+impl fmt::Display for RejectedCell {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}:{}: column '{}': ", self.file, self.line, self.column)?;
+        if self.text.trim().is_empty() {
+            write!(f, "missing value")
+        } else {
+            write!(f, "cannot read '{}' as a number", self.text)
+        }
+    }
+}
+
+/// The cells rejected while loading, and the policy that handled them.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Diagnostics {
+    pub policy: MissingPolicy,
+    pub rejected: Vec<RejectedCell>,
+}
+
+impl Diagnostics {
+    pub fn is_empty(&self) -> bool {
+        self.rejected.is_empty()
+    }
+
+    /// Prints the report to stderr when any cell was rejected.
+    pub fn report(&self) {
+        if !self.is_empty() {
+            eprint!("{}", self);
+        }
+    }
+}
+
+impl fmt::Display for Diagnostics {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let outcome = match self.policy {
+            MissingPolicy::Skip | MissingPolicy::Fail => "their records were skipped",
+            MissingPolicy::Zero => "values were read as 0",
+            MissingPolicy::Gap => "values were left as gaps",
+        };
+        writeln!(f, "Warning: {} cell(s) could not be read; {}:", self.rejected.len(), outcome)?;
+        for cell in &self.rejected {
+            writeln!(f, "  {}", cell)?;
+        }
+        Ok(())
+    }
+}
+
+/// Reads columns from a CSV file under a [`MissingPolicy`], recording every
+/// rejected cell in its [`Diagnostics`].
+///
+/// Cells of text columns (labels, categories, groups) are never parsed; a
+/// record too short to hold one is always skipped and reported.
+///
+/// The file is read once into a [`Table`] that every later read draws from,
+/// after any [`Melt`] and [`Pivot`]. Files over 64 MiB are streamed instead:
+/// each read passes over the file again, parsing the values it asks for as
+/// each record arrives and keeping nothing else.
+pub struct Loader {
+    path: String,
+    encoding: Encoding,
+    dialect: Dialect,
+    stream: Option<bool>,
+    melt: Option<Melt>,
+    pivot: Option<Pivot>,
+    table: OnceCell<Table>,
+    diagnostics: Diagnostics,
+}
+
+impl Loader {
+    pub fn new(path: &str) -> Self {
+        Loader {
+            path: path.to_string(),
+            encoding: Encoding::Auto,
+            dialect: Dialect::default(),
+            stream: None,
+            melt: None,
+            pivot: None,
+            table: OnceCell::new(),
+            diagnostics: Diagnostics::default(),
+        }
+    }
+
+    pub fn missing(mut self, policy: MissingPolicy) -> Self {
+        self.diagnostics.policy = policy;
+        self
+    }
+
+    /// Sets the encoding of the file, detected by default.
+    pub fn encoding(mut self, encoding: Encoding) -> Self {
+        self.encoding = encoding;
+        self.table = OnceCell::new();
+        self
+    }
+
+    /// Sets the delimiter, quoting and rows to skip, comma-separated with
+    /// the header on the first line by default.
+    pub fn dialect(mut self, dialect: Dialect) -> Self {
+        self.dialect = dialect;
+        self.table = OnceCell::new();
+        self
+    }
+
+    /// Streams the file instead of holding it in memory, or never does;
+    /// by default only files over 64 MiB are streamed. Reshaped files are
+    /// always held in memory.
+    pub fn stream(mut self, stream: bool) -> Self {
+        self.stream = Some(stream);
+        self
+    }
+
+    /// Turns the wide file long before any column is read.
+    pub fn melt(mut self, melt: Melt) -> Self {
+        self.melt = Some(melt);
+        self.table = OnceCell::new();
+        self
+    }
+
+    /// Turns the long file, or the melted one, wide before any column is read.
+    pub fn pivot(mut self, pivot: Pivot) -> Self {
+        self.pivot = Some(pivot);
+        self.table = OnceCell::new();
+        self
+    }
+
+    fn streaming(&self) -> bool {
+        self.melt.is_none()
+            && self.pivot.is_none()
+            && self.stream.unwrap_or_else(|| {
+                fs::metadata(&self.path).is_ok_and(|meta| meta.len() > STREAM_BYTES)
+            })
+    }
+
+    /// The whole file, read and reshaped on first use.
+    pub fn table(&self) -> Result<&Table, ChartError> {
+        if let Some(table) = self.table.get() {
+            return Ok(table);
+        }
+        let mut table = Table::read(&self.path, self.encoding, &self.dialect)?;
+        if let Some(melt) = &self.melt {
+            table = melt.apply(&table)?;
+        }
+        if let Some(pivot) = &self.pivot {
+            table = pivot.apply(&table)?;
+        }
+        Ok(self.table.get_or_init(|| table))
+    }
+
+    // A reader of the file from the header row on, decoding it as it goes.
+    fn open(&self) -> Result<csv::Reader<impl BufRead>, ChartError> {
+        let mut source = BufReader::with_capacity(STREAM_BUFFER, open_text(&self.path, self.encoding)?);
+        self.dialect
+            .skip_to_header(&mut source)
+            .map_err(|err| ChartError::io(&self.path, err))?;
+        let sample = source.fill_buf().map_err(|err| ChartError::io(&self.path, err))?;
+        let sample = String::from_utf8_lossy(sample).into_owned();
+        Ok(self.dialect.reader(&sample, source))
+    }
+
+    /// Reads the header row with surrounding whitespace trimmed.
+    pub fn headers(&self) -> Result<Vec<String>, ChartError> {
+        let headers = if self.streaming() {
+            let mut rdr = self.open()?;
+            let headers = rdr.headers().map_err(|err| ChartError::csv(&self.path, err))?;
+            headers.iter().map(|h| h.to_string()).collect()
+        } else {
+            self.table()?.headers().to_vec()
+        };
+        Ok(headers.iter().map(|h| h.trim().to_string()).collect())
+    }
+
+    /// The unit stripped from the values of a column, e.g. `£` or `%`: that
+    /// of the first value written with one.
+    pub fn unit(&self, col: impl Into<Column>) -> Result<Option<String>, ChartError> {
+        Ok(self
+            .cells(col)?
+            .iter()
+            .filter_map(|cell| cell.as_deref())
+            .find_map(|text| parse_value(text).and_then(|(_, unit)| unit)))
+    }
+
+    /// The cells of a column as written, `None` where a record is too short
+    /// to hold one. Nothing is parsed or rejected.
+    pub fn cells(&self, col: impl Into<Column>) -> Result<Vec<Option<String>>, ChartError> {
+        Ok(self.columns(&[col.into()])?.pop().unwrap_or_default())
+    }
+
+    /// The cells of several columns as written, as [`Loader::cells`] gives
+    /// them, read together in a single pass over a streamed file.
+    pub fn columns(&self, cols: &[Column]) -> Result<Vec<Vec<Option<String>>>, ChartError> {
+        let mut columns = vec![Vec::new(); cols.len()];
+        self.read_rows(cols, |_, row| {
+            for (column, cell) in columns.iter_mut().zip(row.cells) {
+                column.push(cell.map(str::to_string));
+            }
+            Ok(())
+        })?;
+        Ok(columns)
+    }
+
+    // Calls `f` with the header names of the requested columns and their
+    // cells in every record, as each record is read from a streamed file.
+    // Short records reach the missing-value policy instead of failing the
+    // read.
+    fn read_rows(
+        &self,
+        cols: &[Column],
+        mut f: impl FnMut(&[String], Row<'_>) -> Result<(), ChartError>,
+    ) -> Result<(), ChartError> {
+        if !self.streaming() {
+            let table = self.table()?;
+            let columns: Vec<&[Option<String>]> =
+                cols.iter().map(|col| table.column(col)).collect::<Result<_, _>>()?;
+            let names: Vec<String> = cols
+                .iter()
+                .map(|col| col.resolve(table.headers()).map(|i| table.headers()[i].trim().to_string()))
+                .collect::<Result<_, _>>()?;
+            for (i, &line) in table.lines().iter().enumerate() {
+                let cells = columns.iter().map(|column| column[i].as_deref()).collect();
+                f(&names, Row { line, cells })?;
+            }
+            return Ok(());
+        }
+
+        let mut rdr = self.open()?;
+        let headers = rdr.headers().map_err(|err| ChartError::csv(&self.path, err))?;
+        let headers: Vec<String> = headers.iter().map(|h| h.to_string()).collect();
+        let cols: Vec<usize> = cols
+            .iter()
+            .map(|col| col.resolve(&headers))
+            .collect::<Result<_, _>>()?;
+        let names: Vec<String> = cols.iter().map(|&col| headers[col].trim().to_string()).collect();
+
+        for_each_record(&self.path, &self.dialect, &mut rdr, |line, record| {
+            f(&names, Row { line, cells: cols.iter().map(|&col| record.get(col)).collect() })
+        })
+    }
+
+    // Reads the requested columns as `read_rows` does, handing `f` a check
+    // that applies the missing-value policy to the cells of each record.
+    fn check_rows(
+        &mut self,
+        cols: &[Column],
+        mut f: impl FnMut(&mut CellCheck, &[String], Row<'_>) -> Result<(), ChartError>,
+    ) -> Result<(), ChartError> {
+        let file = self.path.clone();
+        // Taken out while the file is read, as the rows borrow the loader
+        let mut diagnostics = mem::take(&mut self.diagnostics);
+        let mut check = CellCheck { file: &file, diagnostics: &mut diagnostics };
+        let result = self.read_rows(cols, |names, row| f(&mut check, names, row));
+        self.diagnostics = diagnostics;
+        result
+    }
+
+    /// The cells rejected by the reads so far.
+    pub fn diagnostics(&self) -> &Diagnostics {
+        &self.diagnostics
+    }
+
+    /// Reads numeric columns, one `Vec` per requested column. All columns
+    /// have the same length: a record is kept or skipped as a whole.
+    pub fn numeric(&mut self, cols: &[Column]) -> Result<Vec<Vec<f64>>, ChartError> {
+        let mut columns = vec![Vec::new(); cols.len()];
+
+        self.check_rows(cols, |check, names, row| {
+            if let Some(values) = check.values(row.line, names, &row.cells)? {
+                for (column, value) in columns.iter_mut().zip(values) {
+                    column.push(value);
+                }
+            }
+            Ok(())
+        })?;
+
+        Ok(columns)
+    }
+
+    /// Reads a text label column together with numeric value columns.
+    ///
+    /// Labels and values are pushed together, so a skipped record never
+    /// leaves a dangling label behind.
+    pub fn labeled(
+        &mut self,
+        label_col: impl Into<Column>,
+        value_cols: &[Column],
+    ) -> Result<LabeledColumns, ChartError> {
+        let mut cols = vec![label_col.into()];
+        cols.extend_from_slice(value_cols);
+
+        let mut labels = Vec::new();
+        let mut columns = vec![Vec::new(); value_cols.len()];
+
+        self.check_rows(&cols, |check, names, row| {
+            let Some(mut label) = check.texts(row.line, &names[..1], &row.cells[..1])? else {
+                return Ok(());
+            };
+            if let Some(values) = check.values(row.line, &names[1..], &row.cells[1..])? {
+                labels.push(label.remove(0));
+                for (column, value) in columns.iter_mut().zip(values) {
+                    column.push(value);
+                }
+            }
+            Ok(())
+        })?;
+
+        Ok((labels, columns))
+    }
+
+    /// Reads a long-format table, one row per (category, group, value), into
+    /// one value per category for every group.
+    ///
+    /// Categories and groups keep the order they first appear in. Rows
+    /// repeating a (category, group) pair are summed; pairs with no value
+    /// are `NaN`.
+    pub fn grouped(
+        &mut self,
+        category_col: impl Into<Column>,
+        group_col: impl Into<Column>,
+        value_col: impl Into<Column>,
+    ) -> Result<GroupedColumns, ChartError> {
+        let cols = [category_col.into(), group_col.into(), value_col.into()];
+
+        let mut categories: Vec<String> = Vec::new();
+        let mut groups: Vec<(String, Vec<f64>)> = Vec::new();
+
+        self.check_rows(&cols, |check, names, row| {
+            let Some(keys) = check.texts(row.line, &names[..2], &row.cells[..2])? else {
+                return Ok(());
+            };
+            let Some(values) = check.values(row.line, &names[2..], &row.cells[2..])? else {
+                return Ok(());
+            };
+            let value = values[0];
+
+            let category = match categories.iter().position(|c| *c == keys[0]) {
+                Some(index) => index,
+                None => {
+                    categories.push(keys[0].clone());
+                    for (_, values) in groups.iter_mut() {
+                        values.push(f64::NAN);
+                    }
+                    categories.len() - 1
+                }
+            };
+            let group = match groups.iter().position(|(name, _)| *name == keys[1]) {
+                Some(index) => index,
+                None => {
+                    groups.push((keys[1].clone(), vec![f64::NAN; categories.len()]));
+                    groups.len() - 1
+                }
+            };
+
+            // A gap leaves the pair as it is
+            if !value.is_nan() {
+                let cell = &mut groups[group].1[category];
+                *cell = if cell.is_nan() { value } else { *cell + value };
+            }
+            Ok(())
+        })?;
+
+        Ok((categories, groups))
+    }
+}
+
+// Applies the missing-value policy to the cells of one read, recording the
+// ones it rejects.
+struct CellCheck<'a> {
+    file: &'a str,
+    diagnostics: &'a mut Diagnostics,
+}
+
+impl CellCheck<'_> {
+    // Records a rejected cell, or fails on it under `MissingPolicy::Fail`.
+    fn reject(&mut self, line: u64, column: &str, text: Option<&str>) -> Result<(), ChartError> {
+        let cell = RejectedCell {
+            file: self.file.to_string(),
+            line,
+            column: column.to_string(),
+            text: text.unwrap_or("").to_string(),
+        };
+        if self.diagnostics.policy == MissingPolicy::Fail {
+            return Err(ChartError::Cell(cell));
+        }
+        self.diagnostics.rejected.push(cell);
+        Ok(())
+    }
+
+    // The text cells of a record, or `None` when the record is too short to
+    // hold them; no policy can fill in a label, so such records are skipped.
+    fn texts(
+        &mut self,
+        line: u64,
+        names: &[String],
+        cells: &[Option<&str>],
+    ) -> Result<Option<Vec<String>>, ChartError> {
+        let mut texts = Vec::new();
+        for (name, cell) in names.iter().zip(cells) {
+            match cell {
+                Some(text) => texts.push(text.to_string()),
+                None => {
+                    self.reject(line, name, None)?;
+                    return Ok(None);
+                }
+            }
+        }
+        Ok(Some(texts))
+    }
+
+    // The value cells of a record under the policy, or `None` to skip the
+    // record.
+    fn values(
+        &mut self,
+        line: u64,
+        names: &[String],
+        cells: &[Option<&str>],
+    ) -> Result<Option<Vec<f64>>, ChartError> {
+        let mut values = Vec::new();
+        let mut skip = false;
+        for (name, &cell) in names.iter().zip(cells) {
+            match cell.and_then(parse_number) {
+                Some(value) => values.push(value),
+                None => {
+                    self.reject(line, name, cell)?;
+                    match self.diagnostics.policy {
+                        MissingPolicy::Zero => values.push(0.0),
+                        MissingPolicy::Gap => values.push(f64::NAN),
+                        _ => skip = true,
+                    }
+                }
+            }
+        }
+        Ok(if skip { None } else { Some(values) })
+    }
+}
+
+/// Reads numeric columns from a CSV file, one `Vec` per requested column,
+/// skipping records with a value that is not a number (see
+/// [`parse_value`]) and reporting them on stderr.
+pub fn read_numeric(path: &str, cols: &[Column]) -> Result<Vec<Vec<f64>>, ChartError> {
+    let mut loader = Loader::new(path);
+    let columns = loader.numeric(cols)?;
+    loader.diagnostics().report();
+    Ok(columns)
+}
+
+/// Reads a text label column together with numeric value columns; see
+/// [`Loader::labeled`]. Skipped records are reported on stderr.
+pub fn read_labeled(
+    path: &str,
+    label_col: impl Into<Column>,
+    value_cols: &[Column],
+) -> Result<LabeledColumns, ChartError> {
+    let mut loader = Loader::new(path);
+    let data = loader.labeled(label_col, value_cols)?;
+    loader.diagnostics().report();
+    Ok(data)
+}
+
+/// Reads a long-format table into one value per category for every group;
+/// see [`Loader::grouped`]. Skipped records are reported on stderr.
+pub fn read_grouped(
+    path: &str,
+    category_col: impl Into<Column>,
+    group_col: impl Into<Column>,
+    value_col: impl Into<Column>,
+) -> Result<GroupedColumns, ChartError> {
+    let mut loader = Loader::new(path);
+    let data = loader.grouped(category_col, group_col, value_col)?;
+    loader.diagnostics().report();
+    Ok(data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn unit(unit: &str) -> Option<String> {
+        Some(unit.to_string())
+    }
+
+    fn headers(names: &[&str]) -> Vec<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    #[test]
+    fn resolves_columns_by_trimmed_name() {
+        let headers = headers(&["Rank", " Player", " salary ", "Team"]);
+        assert_eq!(Column::from("Player").resolve(&headers).unwrap(), 1);
+        assert_eq!(Column::from(" salary").resolve(&headers).unwrap(), 2);
+        assert_eq!(Column::from("Team").resolve(&headers).unwrap(), 3);
+    }
+
+    #[test]
+    fn falls_back_to_indices() {
+        let headers = headers(&["2019", "2020", "value"]);
+        assert_eq!(Column::Index(2).resolve(&headers).unwrap(), 2);
+        // A header wins over the index a name could be read as
+        assert_eq!(Column::from("2020").resolve(&headers).unwrap(), 1);
+        assert_eq!(Column::from("0").resolve(&headers).unwrap(), 0);
+        assert_eq!(Column::from("2").resolve(&headers).unwrap(), 2);
+        assert!(Column::Index(3).resolve(&headers).is_err());
+        assert!(Column::from("3").resolve(&headers).is_err());
+    }
+
+    #[test]
+    fn lists_available_headers_when_not_found() {
+        let err = Column::from("Salary").resolve(&headers(&[" Player", " salary"])).unwrap_err();
+        match &err {
+            ChartError::ColumnNotFound { column, available } => {
+                assert_eq!(column, "'Salary'");
+                assert_eq!(available, &["Player", "salary"]);
+            }
+            other => panic!("expected a missing column, got {:?}", other),
+        }
+        assert_eq!(err.to_string(), "column 'Salary' not found; available columns: 'Player', 'salary'");
+        let err = Column::Index(5).resolve(&headers(&["a"])).unwrap_err();
+        assert_eq!(err.to_string(), "column #5 not found; available columns: 'a'");
+    }
+
+    #[test]
+    fn deserializes_names_and_indices() {
+        let cols: Vec<Column> = serde_json::from_str(r#"["Player", 3, " 2020"]"#).unwrap();
+        assert_eq!(cols, vec![Column::from("Player"), Column::Index(3), Column::from(" 2020")]);
+        assert_eq!("Team".parse::<Column>(), Ok(Column::from("Team")));
+    }
+
+    #[test]
+    fn strips_currency_and_percent_units() {
+        assert_eq!(parse_value("£1.92"), Some((1.92, unit("£"))));
+        assert_eq!(parse_value("20.40%"), Some((20.4, unit("%"))));
+        assert_eq!(parse_value("US$ 12"), Some((12.0, unit("US$"))));
+        assert_eq!(parse_value("THB 1,250"), Some((1250.0, unit("THB"))));
+        assert_eq!(parse_value("12.5 kg"), Some((12.5, unit("kg"))));
+        assert_eq!(parse_value("80 km/h"), Some((80.0, unit("km/h"))));
+    }
+
+    #[test]
+    fn reads_grouping_and_decimal_marks() {
+        assert_eq!(parse_value("1,234,567"), Some((1_234_567.0, None)));
+        assert_eq!(parse_value("1.234,5 €"), Some((1234.5, unit("€"))));
+        assert_eq!(parse_value("1 234,5"), Some((1234.5, None)));
+        assert_eq!(parse_value("0,5"), Some((0.5, None)));
+        assert_eq!(parse_value("1'234.5"), Some((1234.5, None)));
+    }
+
+    #[test]
+    fn reads_negative_amounts() {
+        assert_eq!(parse_value("(1,250)"), Some((-1250.0, None)));
+        assert_eq!(parse_value("$(1,250)"), Some((-1250.0, unit("$"))));
+        assert_eq!(parse_value("-£3.50"), Some((-3.5, unit("£"))));
+        assert_eq!(parse_value("\u{2212}4"), Some((-4.0, None)));
+    }
+
+    #[test]
+    fn rejects_text_around_numbers() {
+        assert_eq!(parse_value("Jan 2020"), None);
+        assert_eq!(parse_value("JAN 2020"), None);
+        assert_eq!(parse_value("Room 101"), None);
+        assert_eq!(parse_value("101 per room"), None);
+        assert_eq!(parse_value("$12 USD"), None);
+        assert_eq!(parse_value("n/a"), None);
+    }
+
+    #[test]
+    fn rejects_non_finite_values() {
+        assert_eq!(parse_value("inf"), None);
+        assert_eq!(parse_value("-infinity"), None);
+        assert_eq!(parse_value("NaN"), None);
+        assert_eq!(parse_value(&"9".repeat(400)), None);
+    }
+
+    // Runs `read` on a loader under `policy` over a temporary CSV file
+    // holding `contents`.
+    fn load<T>(
+        name: &str,
+        contents: &str,
+        policy: MissingPolicy,
+        read: impl FnOnce(&mut Loader) -> T,
+    ) -> (T, Diagnostics) {
+        let path = std::env::temp_dir().join(format!("chartkit-{}-{}.csv", name, std::process::id()));
+        std::fs::write(&path, contents).unwrap();
+        let mut loader = Loader::new(path.to_str().unwrap()).missing(policy);
+        let data = read(&mut loader);
+        std::fs::remove_file(&path).unwrap();
+        (data, loader.diagnostics().clone())
+    }
+
+    const CITIES: &str = "city,pop,area\nOslo,700,454\nBergen,,465\nTrondheim,n/a\nStavanger,144,71\n";
+
+    fn rejected(diagnostics: &Diagnostics) -> Vec<(u64, &str, &str)> {
+        diagnostics
+            .rejected
+            .iter()
+            .map(|cell| (cell.line, cell.column.as_str(), cell.text.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn skips_records_with_unreadable_values() {
+        let cols = [Column::from("pop"), Column::from("area")];
+        let (data, diagnostics) = load("skip", CITIES, MissingPolicy::Skip, |loader| loader.labeled("city", &cols));
+
+        // Labels stay aligned with the values of the records kept
+        let (labels, columns) = data.unwrap();
+        assert_eq!(labels, vec!["Oslo", "Stavanger"]);
+        assert_eq!(columns, vec![vec![700.0, 144.0], vec![454.0, 71.0]]);
+        assert_eq!(rejected(&diagnostics), vec![(3, "pop", ""), (4, "pop", "n/a"), (4, "area", "")]);
+    }
+
+    #[test]
+    fn reads_unreadable_values_as_zero() {
+        let cols = [Column::from("pop"), Column::from("area")];
+        let (data, diagnostics) = load("zero", CITIES, MissingPolicy::Zero, |loader| loader.numeric(&cols));
+
+        assert_eq!(data.unwrap(), vec![vec![700.0, 0.0, 0.0, 144.0], vec![454.0, 465.0, 0.0, 71.0]]);
+        assert_eq!(diagnostics.rejected.len(), 3);
+    }
+
+    #[test]
+    fn fails_on_the_first_unreadable_value() {
+        let cols = [Column::from("area"), Column::from("pop")];
+        let (data, diagnostics) = load("fail", CITIES, MissingPolicy::Fail, |loader| loader.labeled("city", &cols));
+
+        match data {
+            Err(ChartError::Cell(cell)) => {
+                assert_eq!((cell.line, cell.column.as_str(), cell.text.as_str()), (3, "pop", ""));
+                assert!(cell.to_string().ends_with(":3: column 'pop': missing value"));
+            }
+            other => panic!("expected a rejected cell, got {:?}", other),
+        }
+        assert!(diagnostics.is_empty());
+    }
+
+    #[test]
+    fn groups_only_the_records_kept() {
+        let sales = "region,product,sales\nNorth,A,5\nNorth,B,x\nSouth,A,3\nNorth,A,2\n";
+        let read = |loader: &mut Loader| loader.grouped("region", "product", "sales").unwrap();
+
+        let ((categories, groups), _) = load("grouped-skip", sales, MissingPolicy::Skip, read);
+        assert_eq!(categories, vec!["North", "South"]);
+        assert_eq!(groups, vec![("A".to_string(), vec![7.0, 3.0])]);
+
+        // A gap still brings in its group, with no value for the pair
+        let ((_, groups), diagnostics) = load("grouped-gap", sales, MissingPolicy::Gap, read);
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[1].0, "B");
+        assert!(groups[1].1.iter().all(|value| value.is_nan()));
+        assert_eq!(rejected(&diagnostics), vec![(3, "sales", "x")]);
+    }
+
+    #[test]
+    fn reports_rejected_cells() {
+        let cell = |line: u64, text: &str| RejectedCell {
+            file: "cities.csv".to_string(),
+            line,
+            column: "pop".to_string(),
+            text: text.to_string(),
+        };
+        let mut diagnostics = Diagnostics { policy: MissingPolicy::Gap, rejected: Vec::new() };
+        assert!(diagnostics.is_empty());
+
+        diagnostics.rejected = vec![cell(3, " "), cell(4, "n/a")];
+        assert_eq!(
+            diagnostics.to_string(),
+            "Warning: 2 cell(s) could not be read; values were left as gaps:\n  \
+             cities.csv:3: column 'pop': missing value\n  \
+             cities.csv:4: column 'pop': cannot read 'n/a' as a number\n"
+        );
+        diagnostics.policy = MissingPolicy::Fail;
+        let report = diagnostics.to_string();
+        assert!(report.starts_with("Warning: 2 cell(s) could not be read; their records were skipped:"));
+    }
+
+    #[test]
+    fn parses_missing_policies() {
+        assert_eq!(" Gap ".parse::<MissingPolicy>(), Ok(MissingPolicy::Gap));
+        assert_eq!("ZERO".parse::<MissingPolicy>(), Ok(MissingPolicy::Zero));
+        assert_eq!(
+            "drop".parse::<MissingPolicy>(),
+            Err("invalid missing-value policy 'drop', expected skip, zero, gap or fail".to_string())
+        );
+    }
+
+    #[test]
+    fn streams_the_same_columns_as_the_table() {
+        let path = std::env::temp_dir().join(format!("chartkit-loader-{}.csv", std::process::id()));
+        std::fs::write(&path, "month,a,b\nJan,1,0\nFeb,2\nMar,3,0\n").unwrap();
+        let path = path.to_str().unwrap();
+        let cols = [Column::from("b"), Column::Index(1)];
+        let streamed = Loader::new(path).stream(true).columns(&cols);
+        let held = Loader::new(path).stream(false).columns(&cols);
+        std::fs::remove_file(path).unwrap();
+
+        let cell = |text: &str| Some(text.to_string());
+        let expected = vec![vec![cell("0"), None, cell("0")], vec![cell("1"), cell("2"), cell("3")]];
+        assert_eq!(streamed.unwrap(), expected);
+        assert_eq!(held.unwrap(), expected);
+    }
+
+    #[test]
+    fn streams_the_same_values_as_the_table() {
+        let path = std::env::temp_dir().join(format!("chartkit-values-{}.csv", std::process::id()));
+        std::fs::write(&path, "month,a,b\nJan,1,x\nFeb,2\nMar,3,4\n").unwrap();
+        let path = path.to_str().unwrap();
+        let cols = [Column::from("a"), Column::from("b")];
+        let read = |stream: bool, policy: MissingPolicy| {
+            let mut loader = Loader::new(path).stream(stream).missing(policy);
+            let data = loader.labeled("month", &cols);
+            (data, loader.diagnostics().clone())
+        };
+        let results: Vec<_> = [true, false]
+            .into_iter()
+            .map(|stream| (read(stream, MissingPolicy::Gap), read(stream, MissingPolicy::Fail)))
+            .collect();
+        std::fs::remove_file(path).unwrap();
+
+        for ((gap, diagnostics), (fail, _)) in results {
+            let (labels, columns) = gap.unwrap();
+            assert_eq!(labels, vec!["Jan", "Feb", "Mar"]);
+            assert_eq!(columns[0], vec![1.0, 2.0, 3.0]);
+            assert!(columns[1][0].is_nan() && columns[1][1].is_nan());
+            assert_eq!(columns[1][2], 4.0);
+            let lines: Vec<u64> = diagnostics.rejected.iter().map(|cell| cell.line).collect();
+            assert_eq!(lines, vec![2, 3]);
+
+            match fail {
+                Err(ChartError::Cell(cell)) => assert_eq!((cell.line, cell.text.as_str()), (2, "x")),
+                other => panic!("expected a rejected cell, got {:?}", other),
+            }
+        }
+    }
+}
@@ -0,0 +1,249 @@
+use serde::{Deserialize, Deserializer};
+use std::io::{self, BufRead, Read};
+
+// Delimiters tried, in order of preference on a tie, when none is set.
+const DELIMITERS: &[u8] = b",;\t|";
+
+// Lines sampled to detect the delimiter.
+const SAMPLE_LINES: usize = 10;
+
+/// How a CSV file is laid out, e.g. for a semicolon-separated export with a
+/// title line above the header and a total row at the end:
+///
+/// ```toml
+/// [data]
+/// path = "export.csv"
+/// delimiter = ";"
+/// header_row = 1
+/// skip_footer = 1
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default)]
+pub struct Dialect {
+    /// Field separator, detected among `,`, `;`, tab and `|` when unset.
+    #[serde(deserialize_with = "deserialize_opt_char")]
+    pub delimiter: Option<char>,
+    #[serde(deserialize_with = "deserialize_char")]
+    pub quote: char,
+    /// Character escaping a quote inside a quoted field, e.g. `\`. Without
+    /// one a quote is escaped by doubling it.
+    #[serde(deserialize_with = "deserialize_opt_char")]
+    pub escape: Option<char>,
+    /// Lines starting with this character are ignored, e.g. `#`.
+    #[serde(deserialize_with = "deserialize_opt_char")]
+    pub comment: Option<char>,
+    /// Zero-based line of the header row; the lines above it are skipped.
+    pub header_row: usize,
+    /// Records to skip after the header, e.g. a row of units.
+    pub skip_rows: usize,
+    /// Records to skip at the end of the file, e.g. totals or notes.
+    pub skip_footer: usize,
+}
+
+impl Default for Dialect {
+    fn default() -> Self {
+        Dialect {
+            delimiter: None,
+            quote: '"',
+            escape: None,
+            comment: None,
+            header_row: 0,
+            skip_rows: 0,
+            skip_footer: 0,
+        }
+    }
+}
+
+impl Dialect {
+    /// `text` from the header row on.
+    pub(crate) fn body<'a>(&self, text: &'a str) -> &'a str {
+        let mut body = text;
+        for _ in 0..self.header_row {
+            body = body.split_once('\n').map_or("", |(_, rest)| rest);
+        }
+        body
+    }
+
+    /// Consumes the lines of `source` above the header row.
+    pub(crate) fn skip_to_header<R: BufRead>(&self, source: &mut R) -> io::Result<()> {
+        let mut line = Vec::new();
+        for _ in 0..self.header_row {
+            line.clear();
+            source.read_until(b'\n', &mut line)?;
+        }
+        Ok(())
+    }
+
+    /// A reader of `source`, starting at the header row, in this dialect;
+    /// `sample` is its beginning, to detect the delimiter from. Records may
+    /// be shorter or longer than the header.
+    pub(crate) fn reader<R: Read>(&self, sample: &str, source: R) -> csv::Reader<R> {
+        let mut builder = csv::ReaderBuilder::new();
+        builder
+            .flexible(true)
+            .delimiter(self.delimiter.map_or_else(|| self.detect_delimiter(sample), |c| c as u8))
+            .quote(self.quote as u8)
+            .comment(self.comment.map(|c| c as u8));
+        if let Some(escape) = self.escape {
+            builder.escape(Some(escape as u8)).double_quote(false);
+        }
+        builder.from_reader(source)
+    }
+
+    /// The delimiter that splits the first lines of `body` into the same
+    /// number of fields as the header, and into the most fields on a tie.
+    /// Falls back to a comma.
+    pub fn detect_delimiter(&self, body: &str) -> u8 {
+        let lines: Vec<&str> = body
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .filter(|line| self.comment.is_none_or(|c| !line.starts_with(c)))
+            .take(SAMPLE_LINES)
+            .collect();
+
+        let mut best = (0, 0, b',');
+        for &delimiter in DELIMITERS {
+            let counts: Vec<usize> = lines.iter().map(|line| self.count(line, delimiter)).collect();
+            let header = counts.first().copied().unwrap_or(0);
+            if header == 0 {
+                continue;
+            }
+            let consistent = counts.iter().filter(|&&count| count == header).count();
+            if (consistent, header) > (best.0, best.1) {
+                best = (consistent, header, delimiter);
+            }
+        }
+        best.2
+    }
+
+    // Occurrences of `delimiter` in `line` outside quoted fields.
+    fn count(&self, line: &str, delimiter: u8) -> usize {
+        let mut quoted = false;
+        let mut count = 0;
+        for byte in line.bytes() {
+            if byte == self.quote as u8 {
+                quoted = !quoted;
+            } else if byte == delimiter && !quoted {
+                count += 1;
+            }
+        }
+        count
+    }
+}
+
+/// Parses a single ASCII character, or one of the names `comma`,
+/// `semicolon`, `tab`, `pipe` and `space`.
+pub fn parse_char(s: &str) -> Result<char, String> {
+    let c = match s {
+        "comma" => ',',
+        "semicolon" => ';',
+        "tab" | "\\t" => '\t',
+        "pipe" => '|',
+        "space" => ' ',
+        _ => {
+            let mut chars = s.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) => c,
+                _ => return Err(format!("expected a single character, got '{}'", s)),
+            }
+        }
+    };
+    if !c.is_ascii() {
+        return Err(format!("'{}' is not an ASCII character", c));
+    }
+    Ok(c)
+}
+
+fn deserialize_char<'de, D: Deserializer<'de>>(deserializer: D) -> Result<char, D::Error> {
+    parse_char(&String::deserialize(deserializer)?).map_err(serde::de::Error::custom)
+}
+
+fn deserialize_opt_char<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<char>, D::Error> {
+    deserialize_char(deserializer).map(Some)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::table::for_each_record;
+
+    // The line and fields of each record `dialect` keeps from `text`.
+    fn records(dialect: &Dialect, text: &str) -> Vec<(u64, Vec<String>)> {
+        let body = dialect.body(text);
+        let mut rdr = dialect.reader(body, body.as_bytes());
+        let mut records = Vec::new();
+        for_each_record("test.csv", dialect, &mut rdr, |line, record| {
+            records.push((line, record.iter().map(|field| field.to_string()).collect()));
+            Ok(())
+        })
+        .unwrap();
+        records
+    }
+
+    fn detect(body: &str) -> char {
+        Dialect::default().detect_delimiter(body) as char
+    }
+
+    #[test]
+    fn detects_delimiters() {
+        assert_eq!(detect("month,value\nJan,1\n"), ',');
+        assert_eq!(detect("month;value\nJan;1,5\nFeb;2,5\n"), ';');
+        assert_eq!(detect("month\tvalue\nJan\t1\n"), '\t');
+        assert_eq!(detect("month|value\nJan|1\n"), '|');
+    }
+
+    #[test]
+    fn falls_back_to_a_comma() {
+        assert_eq!(detect(""), ',');
+        assert_eq!(detect("value\n1\n2\n"), ',');
+    }
+
+    #[test]
+    fn prefers_the_delimiter_consistent_with_the_header() {
+        // Two commas in the header, but only one on the records
+        assert_eq!(detect("a;b,c,d\n1;2,3\n4;5,6\n"), ';');
+    }
+
+    #[test]
+    fn ignores_delimiters_in_quotes_and_comments() {
+        assert_eq!(detect("\"a,b,c\";d\n\"x,y,z\";1\n"), ';');
+        let dialect = Dialect { comment: Some('#'), ..Dialect::default() };
+        assert_eq!(dialect.detect_delimiter("# exported, by, a tool\na;b\n1;2\n"), b';');
+    }
+
+    #[test]
+    fn starts_at_the_header_row() {
+        let dialect = Dialect { header_row: 2, ..Dialect::default() };
+        let text = "Passengers by month\nSource: BTS\nmonth,value\nJan,1\nFeb,2\n";
+        assert_eq!(dialect.body(text), "month,value\nJan,1\nFeb,2\n");
+        assert_eq!(
+            records(&dialect, text),
+            vec![(4, vec!["Jan".to_string(), "1".to_string()]), (5, vec!["Feb".to_string(), "2".to_string()])]
+        );
+
+        let mut source = text.as_bytes();
+        dialect.skip_to_header(&mut source).unwrap();
+        assert_eq!(source, dialect.body(text).as_bytes());
+    }
+
+    #[test]
+    fn skips_rows_after_the_header_and_the_footer() {
+        let dialect = Dialect { header_row: 1, skip_rows: 1, skip_footer: 2, ..Dialect::default() };
+        let text = "Report\nmonth,value\n,persons\nJan,1\nFeb,2\nTotal,3\nNotes: provisional\n";
+        assert_eq!(
+            records(&dialect, text),
+            vec![(4, vec!["Jan".to_string(), "1".to_string()]), (5, vec!["Feb".to_string(), "2".to_string()])]
+        );
+
+        let everything = Dialect { skip_footer: 10, ..Dialect::default() };
+        assert!(records(&everything, "month,value\nJan,1\n").is_empty());
+    }
+
+    #[test]
+    fn parses_characters_and_names() {
+        assert_eq!(parse_char("tab"), Ok('\t'));
+        assert_eq!(parse_char(";"), Ok(';'));
+        assert!(parse_char("ab").is_err());
+        assert!(parse_char("،").is_err());
+    }
+}
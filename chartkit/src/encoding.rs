@@ -0,0 +1,224 @@
+use crate::error::ChartError;
+use encoding_rs::{UTF_16BE, UTF_16LE, UTF_8, WINDOWS_874};
+use encoding_rs_io::DecodeReaderBytesBuilder;
+use serde::{Deserialize, Deserializer};
+use std::fmt;
+use std::fs::{self, File};
+use std::io::{self, Read};
+use std::str::FromStr;
+
+// Bytes read ahead to detect the encoding of a streamed file.
+const SNIFF_BYTES: usize = 64 * 1024;
+
+/// The character encoding of a CSV file. Files are converted to UTF-8 before
+/// parsing, with any byte order mark removed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Encoding {
+    /// Detected from the file: a byte order mark if there is one, otherwise
+    /// UTF-16 when the file holds zero bytes, UTF-8 when the bytes are valid
+    /// UTF-8 and Windows-874 (Thai) failing that.
+    #[default]
+    Auto,
+    Utf8,
+    Utf16Le,
+    Utf16Be,
+    /// Thai Windows-874, a superset of TIS-620, as written by Thai Excel and
+    /// many government exports.
+    Windows874,
+}
+
+impl Encoding {
+    fn codec(&self) -> Option<&'static encoding_rs::Encoding> {
+        match self {
+            Encoding::Auto => None,
+            Encoding::Utf8 => Some(UTF_8),
+            Encoding::Utf16Le => Some(UTF_16LE),
+            Encoding::Utf16Be => Some(UTF_16BE),
+            Encoding::Windows874 => Some(WINDOWS_874),
+        }
+    }
+
+    /// Converts `bytes` to UTF-8 text, or `None` when they are not valid in
+    /// this encoding. Detection never fails: bytes that are not UTF-8 are
+    /// read as Windows-874, which maps nearly every byte.
+    pub fn decode(&self, bytes: &[u8]) -> Option<String> {
+        let codec = match self.codec() {
+            Some(codec) => codec,
+            None => detect(bytes),
+        };
+        let (text, malformed) = codec.decode_with_bom_removal(bytes);
+        if malformed && *self != Encoding::Auto {
+            return None;
+        }
+        Some(text.into_owned())
+    }
+}
+
+// The encoding of `bytes` going by its byte order mark or, without one, by
+// its content.
+fn detect(bytes: &[u8]) -> &'static encoding_rs::Encoding {
+    if let Some((codec, _)) = encoding_rs::Encoding::for_bom(bytes) {
+        return codec;
+    }
+    // Only UTF-16 text holds zero bytes: the high byte of ASCII characters
+    let sample = &bytes[..bytes.len().min(4096)];
+    let zeros = |offset: usize| sample.iter().skip(offset).step_by(2).filter(|&&b| b == 0).count();
+    let (even, odd) = (zeros(0), zeros(1));
+    if odd > even {
+        return UTF_16LE;
+    }
+    if even > odd {
+        return UTF_16BE;
+    }
+    match std::str::from_utf8(bytes) {
+        Ok(_) => UTF_8,
+        // A sample may end partway through a character
+        Err(err) if err.error_len().is_none() => UTF_8,
+        Err(_) => WINDOWS_874,
+    }
+}
+
+impl fmt::Display for Encoding {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Encoding::Auto => "auto",
+            Encoding::Utf8 => "utf-8",
+            Encoding::Utf16Le => "utf-16le",
+            Encoding::Utf16Be => "utf-16be",
+            Encoding::Windows874 => "windows-874",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl FromStr for Encoding {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().replace('_', "-").as_str() {
+            "auto" => Ok(Encoding::Auto),
+            "utf-8" | "utf8" => Ok(Encoding::Utf8),
+            "utf-16" | "utf-16le" | "utf16" | "utf16le" => Ok(Encoding::Utf16Le),
+            "utf-16be" | "utf16be" => Ok(Encoding::Utf16Be),
+            "windows-874" | "cp874" | "tis-620" | "tis620" | "iso-8859-11" => Ok(Encoding::Windows874),
+            _ => Err(format!(
+                "unknown encoding '{}', expected auto, utf-8, utf-16le, utf-16be, tis-620 or windows-874",
+                s
+            )),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Encoding {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+/// Reads a whole file as text in `encoding`.
+pub fn read_text(path: &str, encoding: Encoding) -> Result<String, ChartError> {
+    let bytes = fs::read(path).map_err(|err| ChartError::io(path, err))?;
+    encoding.decode(&bytes).ok_or_else(|| ChartError::Decode {
+        path: path.to_string(),
+        encoding,
+    })
+}
+
+/// Opens a file for reading as UTF-8 text in `encoding`, converting it as it
+/// is read. Detection looks at the first 64 KiB only, and bytes that are not
+/// valid in the encoding are replaced rather than reported.
+pub fn open_text(path: &str, encoding: Encoding) -> Result<impl Read, ChartError> {
+    let mut file = File::open(path).map_err(|err| ChartError::io(path, err))?;
+    let mut head = Vec::with_capacity(SNIFF_BYTES);
+    (&mut file)
+        .take(SNIFF_BYTES as u64)
+        .read_to_end(&mut head)
+        .map_err(|err| ChartError::io(path, err))?;
+
+    let codec = match encoding.codec() {
+        Some(codec) => codec,
+        None => detect(&head),
+    };
+    Ok(DecodeReaderBytesBuilder::new()
+        .encoding(Some(codec))
+        .bom_override(true)
+        .strip_bom(true)
+        .build(io::Cursor::new(head).chain(file)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEXT: &str = "เดือน,ผู้โดยสาร\nมกราคม,1250\n";
+
+    fn utf16(text: &str, little_endian: bool) -> Vec<u8> {
+        text.encode_utf16()
+            .flat_map(|unit| if little_endian { unit.to_le_bytes() } else { unit.to_be_bytes() })
+            .collect()
+    }
+
+    #[test]
+    fn detects_byte_order_marks() {
+        let mut utf8 = b"\xEF\xBB\xBF".to_vec();
+        utf8.extend(TEXT.as_bytes());
+        assert_eq!(Encoding::Auto.decode(&utf8).as_deref(), Some(TEXT));
+
+        let mut le = vec![0xFF, 0xFE];
+        le.extend(utf16(TEXT, true));
+        assert_eq!(Encoding::Auto.decode(&le).as_deref(), Some(TEXT));
+
+        let mut be = vec![0xFE, 0xFF];
+        be.extend(utf16(TEXT, false));
+        assert_eq!(Encoding::Auto.decode(&be).as_deref(), Some(TEXT));
+    }
+
+    #[test]
+    fn detects_utf16_without_a_byte_order_mark() {
+        let text = "month,value\nJan,1250\n";
+        assert_eq!(Encoding::Auto.decode(&utf16(text, true)).as_deref(), Some(text));
+        assert_eq!(Encoding::Auto.decode(&utf16(text, false)).as_deref(), Some(text));
+    }
+
+    #[test]
+    fn falls_back_to_thai_when_not_utf8() {
+        let (bytes, _, _) = WINDOWS_874.encode(TEXT);
+        assert!(std::str::from_utf8(&bytes).is_err());
+        assert_eq!(Encoding::Auto.decode(&bytes).as_deref(), Some(TEXT));
+        assert_eq!(Encoding::Windows874.decode(&bytes).as_deref(), Some(TEXT));
+    }
+
+    #[test]
+    fn detects_utf8_cut_off_mid_character() {
+        let bytes = TEXT.as_bytes();
+        // The first Thai character takes three bytes
+        assert_eq!(detect(&bytes[..2]), UTF_8);
+        assert_eq!(detect(bytes), UTF_8);
+    }
+
+    #[test]
+    fn rejects_bytes_invalid_in_a_given_encoding() {
+        let (bytes, _, _) = WINDOWS_874.encode(TEXT);
+        assert_eq!(Encoding::Utf8.decode(&bytes), None);
+    }
+
+    #[test]
+    fn streams_in_the_detected_encoding() {
+        let path = std::env::temp_dir().join(format!("chartkit-encoding-{}.csv", std::process::id()));
+        fs::write(&path, utf16(TEXT, true)).unwrap();
+        let mut text = String::new();
+        let result = open_text(path.to_str().unwrap(), Encoding::Auto).map(|mut rdr| rdr.read_to_string(&mut text));
+        fs::remove_file(&path).unwrap();
+        assert!(matches!(result, Ok(Ok(_))));
+        assert_eq!(text, TEXT);
+    }
+
+    #[test]
+    fn parses_encoding_names() {
+        assert_eq!("TIS-620".parse(), Ok(Encoding::Windows874));
+        assert_eq!("utf_16".parse(), Ok(Encoding::Utf16Le));
+        assert!("latin-1".parse::<Encoding>().is_err());
+    }
+}
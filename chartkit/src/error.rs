@@ -0,0 +1,147 @@
+use crate::data::RejectedCell;
+use crate::encoding::Encoding;
+use plotters::drawing::DrawingAreaErrorKind;
+use std::error::Error;
+use std::fmt;
+use std::io;
+
+/// Everything that can go wrong between reading a CSV file and writing the
+/// chart, with the file, column or value involved.
+#[derive(Debug)]
+pub enum ChartError {
+    /// A file could not be opened or read.
+    Io { path: String, source: io::Error },
+    /// The CSV reader failed on a malformed record.
+    Csv { path: String, source: csv::Error },
+    /// A file is not valid text in the encoding it was read with.
+    Decode { path: String, encoding: Encoding },
+    /// A chart spec file is not valid TOML or JSON, or not a spec.
+    Spec { path: String, message: String },
+    /// A column is not in the header row.
+    ColumnNotFound { column: String, available: Vec<String> },
+    /// The data or spec lacks something the chart needs, e.g. a `columns.z`
+    /// binding for a 3D scatter plot.
+    Schema(String),
+    /// A value is not in the expected form, e.g. a date or a color.
+    Parse { text: String, expected: String },
+    /// A data cell rejected under [`crate::data::MissingPolicy::Fail`].
+    Cell(RejectedCell),
+    /// Records filling the same cell hold values that cannot be combined,
+    /// e.g. a pivoted pair given in two units.
+    Conflict(String),
+    /// There is nothing to plot.
+    EmptyData(String),
+    /// An axis range or size cannot be drawn.
+    Range(String),
+    /// Plotters failed to draw or save the chart.
+    Render(String),
+}
+
+impl ChartError {
+    pub(crate) fn io(path: &str, source: io::Error) -> Self {
+        ChartError::Io { path: path.to_string(), source }
+    }
+
+    pub(crate) fn csv(path: &str, source: csv::Error) -> Self {
+        ChartError::Csv { path: path.to_string(), source }
+    }
+
+    pub(crate) fn parse(text: &str, expected: &str) -> Self {
+        ChartError::Parse {
+            text: text.to_string(),
+            expected: expected.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for ChartError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ChartError::Io { path, source } => write!(f, "{}: {}", path, source),
+            ChartError::Csv { path, source } => write!(f, "{}: {}", path, source),
+            ChartError::Decode { path, encoding } => write!(f, "{}: not valid {} text", path, encoding),
+            ChartError::Spec { path, message } => write!(f, "{}: {}", path, message),
+            ChartError::ColumnNotFound { column, available } => write!(
+                f,
+                "column {} not found; available columns: {}",
+                column,
+                available
+                    .iter()
+                    .map(|header| format!("'{}'", header))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+            ChartError::Schema(message) => write!(f, "{}", message),
+            ChartError::Parse { text, expected } => write!(f, "cannot parse '{}' as {}", text, expected),
+            ChartError::Cell(cell) => write!(f, "{}", cell),
+            ChartError::Conflict(message) => write!(f, "{}", message),
+            ChartError::EmptyData(message) => write!(f, "{}", message),
+            ChartError::Range(message) => write!(f, "{}", message),
+            ChartError::Render(message) => write!(f, "cannot draw chart: {}", message),
+        }
+    }
+}
+
+impl Error for ChartError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ChartError::Io { source, .. } => Some(source),
+            ChartError::Csv { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+impl<E: Error + Send + Sync> From<DrawingAreaErrorKind<E>> for ChartError {
+    fn from(err: DrawingAreaErrorKind<E>) -> Self {
+        ChartError::Render(err.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn names_the_file_involved() {
+        let err = ChartError::io("sales.csv", io::Error::new(io::ErrorKind::NotFound, "no such file"));
+        assert_eq!(err.to_string(), "sales.csv: no such file");
+        let err = ChartError::Decode { path: "sales.csv".to_string(), encoding: Encoding::Utf16Le };
+        assert_eq!(err.to_string(), "sales.csv: not valid utf-16le text");
+        let err = ChartError::Spec { path: "sales.toml".to_string(), message: "missing field `data`".to_string() };
+        assert_eq!(err.to_string(), "sales.toml: missing field `data`");
+    }
+
+    #[test]
+    fn describes_values_and_drawing() {
+        assert_eq!(ChartError::parse("2020-13-01", "a date").to_string(), "cannot parse '2020-13-01' as a date");
+        assert_eq!(ChartError::Render("Bad layout".to_string()).to_string(), "cannot draw chart: Bad layout");
+        let err = ChartError::EmptyData("no bars to draw".to_string());
+        assert_eq!(err.to_string(), "no bars to draw");
+        let cell = RejectedCell {
+            file: "sales.csv".to_string(),
+            line: 7,
+            column: "total".to_string(),
+            text: "n/a".to_string(),
+        };
+        assert_eq!(ChartError::Cell(cell).to_string(), "sales.csv:7: column 'total': cannot read 'n/a' as a number");
+    }
+
+    #[test]
+    fn keeps_the_underlying_error_as_source() {
+        let err = ChartError::io("sales.csv", io::Error::new(io::ErrorKind::PermissionDenied, "denied"));
+        assert_eq!(err.source().unwrap().to_string(), "denied");
+        let source = csv::Error::from(io::Error::new(io::ErrorKind::UnexpectedEof, "truncated"));
+        assert!(ChartError::csv("sales.csv", source).source().is_some());
+        assert!(ChartError::Schema("no z column".to_string()).source().is_none());
+    }
+
+    #[test]
+    fn wraps_drawing_errors() {
+        let err = ChartError::from(DrawingAreaErrorKind::<io::Error>::LayoutError);
+        match err {
+            ChartError::Render(message) => assert_eq!(message, "Bad layout"),
+            other => panic!("expected a render error, got {:?}", other),
+        }
+    }
+}
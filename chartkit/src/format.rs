@@ -0,0 +1,371 @@
+use plotters::data::float::FloatPrettyPrinter;
+use serde::{Deserialize, Deserializer};
+use std::str::FromStr;
+
+/// How a number is written on an axis.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NumberStyle {
+    /// Digits only, e.g. `26000000`; with `decimals` a fixed-point number.
+    #[default]
+    #[serde(alias = "fixed")]
+    Plain,
+    /// Grouped digits, e.g. `26,000,000`.
+    Thousands,
+    /// Abbreviated with an SI suffix, e.g. `26M` or `73.5k`.
+    Si,
+    /// Percentage points followed by `%`; `12.5` is written `12.5%`.
+    Percent,
+    /// Grouped digits with a currency symbol, e.g. `$1,250`.
+    Currency,
+}
+
+impl FromStr for NumberStyle {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "plain" | "fixed" => Ok(NumberStyle::Plain),
+            "thousands" => Ok(NumberStyle::Thousands),
+            "si" => Ok(NumberStyle::Si),
+            "percent" => Ok(NumberStyle::Percent),
+            "currency" => Ok(NumberStyle::Currency),
+            _ => Err(format!(
+                "invalid number format '{}', expected plain, fixed, thousands, si, percent or currency",
+                s
+            )),
+        }
+    }
+}
+
+/// Digit grouping and decimal mark conventions, named after the separators
+/// they use. Parsed from a language tag such as `en`, `th`, `de-DE` or
+/// `fr`, or from the convention name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Locale {
+    /// `1,234.5`: English, Thai, Chinese, Japanese, ...
+    #[default]
+    Comma,
+    /// `1.234,5`: German, Spanish, Italian, Dutch, Indonesian, ...
+    Dot,
+    /// `1 234,5`: French, Russian, Polish, Swedish, ...
+    Space,
+    /// `1'234.5`: Swiss.
+    Apostrophe,
+}
+
+impl Locale {
+    fn group_separator(&self) -> char {
+        match self {
+            Locale::Comma => ',',
+            Locale::Dot => '.',
+            Locale::Space => '\u{a0}',
+            Locale::Apostrophe => '\'',
+        }
+    }
+
+    fn decimal_mark(&self) -> char {
+        match self {
+            Locale::Comma | Locale::Apostrophe => '.',
+            Locale::Dot | Locale::Space => ',',
+        }
+    }
+
+    // Whether a currency symbol follows the amount, as in `1.234 €`.
+    fn symbol_after(&self) -> bool {
+        matches!(self, Locale::Dot | Locale::Space)
+    }
+}
+
+impl FromStr for Locale {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let tag = s.trim().to_ascii_lowercase().replace('_', "-");
+        if tag == "de-ch" || tag == "it-ch" || tag == "fr-ch" {
+            return Ok(Locale::Apostrophe);
+        }
+        match tag.split('-').next().unwrap_or("") {
+            "comma" | "en" | "th" | "zh" | "ja" | "ko" | "ms" | "hi" => Ok(Locale::Comma),
+            "dot" | "de" | "es" | "it" | "nl" | "pt" | "id" | "vi" | "tr" | "da" | "el" => {
+                Ok(Locale::Dot)
+            }
+            "space" | "fr" | "ru" | "pl" | "cs" | "sk" | "sv" | "fi" | "nb" | "no" | "uk" => {
+                Ok(Locale::Space)
+            }
+            "apostrophe" | "ch" => Ok(Locale::Apostrophe),
+            _ => Err(format!(
+                "unknown locale '{}', expected a language tag such as en, th, de or fr",
+                s
+            )),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Locale {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+// SI prefixes from kilo up, each a thousand times the last.
+const SI_SUFFIXES: &[&str] = &["k", "M", "G", "T", "P"];
+
+/// Tick label formatting for a numeric axis, e.g.
+///
+/// ```toml
+/// [axes]
+/// y_format = "si"
+/// x_format = { style = "currency", symbol = "฿", locale = "th", decimals = 0 }
+/// ```
+///
+/// Without `decimals` numbers are written as short as they can: up to one
+/// decimal after an SI suffix and up to three otherwise.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct NumberFormat {
+    pub style: NumberStyle,
+    pub decimals: Option<usize>,
+    pub locale: Locale,
+    /// Currency symbol, `$` when unset.
+    pub symbol: Option<String>,
+}
+
+impl NumberFormat {
+    pub fn new(style: NumberStyle) -> Self {
+        NumberFormat {
+            style,
+            ..NumberFormat::default()
+        }
+    }
+
+    pub fn decimals(mut self, decimals: usize) -> Self {
+        self.decimals = Some(decimals);
+        self
+    }
+
+    pub fn locale(mut self, locale: Locale) -> Self {
+        self.locale = locale;
+        self
+    }
+
+    pub fn symbol(mut self, symbol: &str) -> Self {
+        self.symbol = Some(symbol.to_string());
+        self
+    }
+
+    /// Writes `value` in this format.
+    pub fn format(&self, value: f64) -> String {
+        if !value.is_finite() {
+            return value.to_string();
+        }
+        // No sign on values that round to zero
+        let sign = if value < 0.0 && self.round(-value, 3) > 0.0 { "-" } else { "" };
+        let magnitude = value.abs();
+
+        match self.style {
+            NumberStyle::Plain => format!("{}{}", sign, self.number(magnitude, 3, false)),
+            NumberStyle::Thousands => format!("{}{}", sign, self.number(magnitude, 3, true)),
+            NumberStyle::Percent => format!("{}{}%", sign, self.number(magnitude, 3, true)),
+            NumberStyle::Si => {
+                let mut scaled = magnitude;
+                let mut suffix = "";
+                for prefix in SI_SUFFIXES {
+                    // Round first so 999,960 reads 1M rather than 1000k
+                    if self.round(scaled, 1) < 1000.0 {
+                        break;
+                    }
+                    scaled /= 1000.0;
+                    suffix = prefix;
+                }
+                format!("{}{}{}", sign, self.number(scaled, 1, false), suffix)
+            }
+            NumberStyle::Currency => {
+                let symbol = self.symbol.as_deref().unwrap_or("$");
+                let amount = self.number(magnitude, 2, true);
+                if self.locale.symbol_after() {
+                    format!("{}{} {}", sign, amount, symbol)
+                } else {
+                    format!("{}{}{}", sign, symbol, amount)
+                }
+            }
+        }
+    }
+
+    // `value` rounded to the configured decimals, or to `max_decimals`.
+    fn round(&self, value: f64, max_decimals: usize) -> f64 {
+        let factor = 10f64.powi(self.decimals.unwrap_or(max_decimals) as i32);
+        (value * factor).round() / factor
+    }
+
+    // A non-negative `value` with the locale's decimal mark, and its digits
+    // grouped when `grouped`. Without fixed decimals trailing zeros are
+    // dropped.
+    fn number(&self, value: f64, max_decimals: usize, grouped: bool) -> String {
+        let text = format!("{:.*}", self.decimals.unwrap_or(max_decimals), value);
+        let (integer, fraction) = match text.split_once('.') {
+            Some((integer, fraction)) => (integer.to_string(), fraction.to_string()),
+            None => (text, String::new()),
+        };
+        let fraction = if self.decimals.is_some() {
+            fraction
+        } else {
+            fraction.trim_end_matches('0').to_string()
+        };
+
+        let mut out = if grouped {
+            group_digits(&integer, self.locale.group_separator())
+        } else {
+            integer
+        };
+        if !fraction.is_empty() {
+            out.push(self.locale.decimal_mark());
+            out.push_str(&fraction);
+        }
+        out
+    }
+}
+
+// Inserts `separator` between each group of three digits.
+fn group_digits(digits: &str, separator: char) -> String {
+    let mut out = String::new();
+    for (i, digit) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            out.push(separator);
+        }
+        out.push(digit);
+    }
+    out
+}
+
+/// A tick label for `value`: written in `format` when one is set, otherwise
+/// the way plotters writes it by default (`2500.0`).
+pub fn axis_label(format: Option<&NumberFormat>, value: f64) -> String {
+    match format {
+        Some(format) => format.format(value),
+        None => FloatPrettyPrinter {
+            allow_scientific: false,
+            min_decimal: 1,
+            max_decimal: 5,
+        }
+        .print(value),
+    }
+}
+
+impl FromStr for NumberFormat {
+    type Err = String;
+
+    /// Parses a style optionally followed by a decimal count, e.g. `si`,
+    /// `thousands` or `fixed:2`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (style, decimals) = match s.split_once(':') {
+            Some((style, decimals)) => {
+                let decimals = decimals
+                    .trim()
+                    .parse()
+                    .map_err(|_| format!("invalid decimal count in number format '{}'", s))?;
+                (style, Some(decimals))
+            }
+            None => (s, None),
+        };
+        Ok(NumberFormat {
+            style: style.parse()?,
+            decimals,
+            ..NumberFormat::default()
+        })
+    }
+}
+
+impl<'de> Deserialize<'de> for NumberFormat {
+    /// Accepts the string forms of `FromStr` or a table with `style`,
+    /// `decimals`, `locale` and `symbol`.
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize, Default)]
+        #[serde(default, deny_unknown_fields)]
+        struct Table {
+            style: NumberStyle,
+            decimals: Option<usize>,
+            locale: Locale,
+            symbol: Option<String>,
+        }
+
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Raw {
+            Text(String),
+            Table(Table),
+        }
+
+        match Raw::deserialize(deserializer)? {
+            Raw::Text(text) => text.parse().map_err(serde::de::Error::custom),
+            Raw::Table(table) => Ok(NumberFormat {
+                style: table.style,
+                decimals: table.decimals,
+                locale: table.locale,
+                symbol: table.symbol,
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn format(style: NumberStyle) -> NumberFormat {
+        NumberFormat::new(style)
+    }
+
+    #[test]
+    fn groups_thousands() {
+        assert_eq!(format(NumberStyle::Thousands).format(26_000_000.0), "26,000,000");
+        assert_eq!(format(NumberStyle::Thousands).format(73_485.0), "73,485");
+        assert_eq!(format(NumberStyle::Thousands).format(-1_234.5), "-1,234.5");
+        assert_eq!(format(NumberStyle::Thousands).format(-0.0001), "0");
+    }
+
+    #[test]
+    fn abbreviates_with_si_suffixes() {
+        assert_eq!(format(NumberStyle::Si).format(26_000_000.0), "26M");
+        assert_eq!(format(NumberStyle::Si).format(73_485.0), "73.5k");
+        assert_eq!(format(NumberStyle::Si).format(999_960.0), "1M");
+        assert_eq!(format(NumberStyle::Si).format(950.0), "950");
+    }
+
+    #[test]
+    fn writes_percentages_and_currency() {
+        assert_eq!(format(NumberStyle::Percent).format(12.5), "12.5%");
+        assert_eq!(format(NumberStyle::Currency).format(1_250.0), "$1,250");
+        assert_eq!(format(NumberStyle::Currency).format(-1_250.555), "-$1,250.56");
+        assert_eq!(format(NumberStyle::Currency).symbol("£").decimals(2).format(3.2), "£3.20");
+    }
+
+    #[test]
+    fn follows_the_locale() {
+        let th: Locale = "th".parse().unwrap();
+        let de: Locale = "de-DE".parse().unwrap();
+        assert_eq!(format(NumberStyle::Currency).symbol("฿").locale(th).format(1_250.0), "฿1,250");
+        assert_eq!(format(NumberStyle::Thousands).locale(de).format(1_234.5), "1.234,5");
+        assert_eq!(
+            format(NumberStyle::Currency).symbol("€").locale(de).decimals(2).format(1_234.5),
+            "1.234,50 €"
+        );
+        assert_eq!(format(NumberStyle::Thousands).locale(Locale::Space).format(1_234.5), "1\u{a0}234,5");
+        assert!("xx".parse::<Locale>().is_err());
+    }
+
+    #[test]
+    fn parses_styles_with_decimals() {
+        assert_eq!("fixed:2".parse::<NumberFormat>().unwrap().format(3.2), "3.20");
+        assert_eq!("si".parse::<NumberFormat>(), Ok(format(NumberStyle::Si)));
+        assert!("fixed:x".parse::<NumberFormat>().is_err());
+        assert!("words".parse::<NumberFormat>().is_err());
+    }
+
+    #[test]
+    fn labels_unformatted_axes_like_plotters() {
+        assert_eq!(axis_label(None, 2500.0), "2500.0");
+        assert_eq!(axis_label(Some(&format(NumberStyle::Si)), 2500.0), "2.5k");
+    }
+}
@@ -0,0 +1,183 @@
+use crate::axis::AxisScale;
+use crate::binning::{Bin, BinRule};
+use crate::error::ChartError;
+use crate::format::{axis_label, NumberFormat};
+use plotters::prelude::*;
+use std::ops::Range;
+
+/// A frequency histogram over a single numeric column.
+pub struct HistogramChart {
+    values: Vec<f64>,
+    bins: BinRule,
+    caption: String,
+    size: (u32, u32),
+    x_desc: String,
+    y_desc: String,
+    y_scale: AxisScale,
+    x_format: Option<NumberFormat>,
+    y_format: Option<NumberFormat>,
+    color: RGBAColor,
+}
+
+impl HistogramChart {
+    pub fn new(values: Vec<f64>) -> Self {
+        HistogramChart {
+            values,
+            bins: BinRule::default(),
+            caption: String::new(),
+            size: (800, 480),
+            x_desc: String::new(),
+            y_desc: "Frequency".to_string(),
+            y_scale: AxisScale::from_zero(),
+            x_format: None,
+            y_format: None,
+            color: BLUE.mix(0.5),
+        }
+    }
+
+    pub fn bins(mut self, rule: BinRule) -> Self {
+        self.bins = rule;
+        self
+    }
+
+    pub fn caption(mut self, caption: &str) -> Self {
+        self.caption = caption.to_string();
+        self
+    }
+
+    pub fn size(mut self, width: u32, height: u32) -> Self {
+        self.size = (width, height);
+        self
+    }
+
+    pub fn x_desc(mut self, desc: &str) -> Self {
+        self.x_desc = desc.to_string();
+        self
+    }
+
+    pub fn y_desc(mut self, desc: &str) -> Self {
+        self.y_desc = desc.to_string();
+        self
+    }
+
+    /// Overrides the frequency axis, which by default fits the tallest bin.
+    pub fn y_scale(mut self, scale: AxisScale) -> Self {
+        self.y_scale = scale;
+        self
+    }
+
+    /// Writes the bin edge labels in `format`.
+    pub fn x_format(mut self, format: NumberFormat) -> Self {
+        self.x_format = Some(format);
+        self
+    }
+
+    /// Writes the frequency labels in `format`, e.g. with thousands
+    /// separators or SI suffixes.
+    pub fn y_format(mut self, format: NumberFormat) -> Self {
+        self.y_format = Some(format);
+        self
+    }
+
+    pub fn color(mut self, color: RGBAColor) -> Self {
+        self.color = color;
+        self
+    }
+
+    /// The bins the chart draws.
+    pub fn compute_bins(&self) -> Vec<Bin> {
+        self.bins.bin(&self.values)
+    }
+
+    // The x-axis spans the bins exactly; the frequency axis fits the counts.
+    fn ranges(&self, bins: &[Bin]) -> Result<(Range<f64>, Range<f64>), ChartError> {
+        let x_range = match (bins.first(), bins.last()) {
+            (Some(first), Some(last)) => first.start..last.end,
+            _ => return Err(ChartError::EmptyData("histogram has no values to bin".to_string())),
+        };
+        Ok((x_range, self.y_scale.fit(bins.iter().map(|bin| bin.count as f64))))
+    }
+
+    pub fn generate_chart(&self, output_path: &str) -> Result<(), ChartError> {
+        let bins = self.compute_bins();
+        let (x_range, y_range) = self.ranges(&bins)?;
+
+        let root = BitMapBackend::new(output_path, self.size).into_drawing_area();
+        root.fill(&WHITE)?;
+
+        let mut chart = ChartBuilder::on(&root)
+            .x_label_area_size(35)
+            .y_label_area_size(40)
+            .margin(5)
+            .caption(&self.caption, ("sans-serif", 50.0))
+            .build_cartesian_2d(x_range, y_range)?;
+
+        chart
+            .configure_mesh()
+            .disable_x_mesh()
+            .x_labels(bins.len() + 1)
+            .x_label_formatter(&|x| axis_label(self.x_format.as_ref(), *x))
+            .y_label_formatter(&|y| match &self.y_format {
+                Some(format) => format.format(*y),
+                None => format!("{}", y.round()),
+            })
+            .x_desc(&self.x_desc)
+            .y_desc(&self.y_desc)
+            .bold_line_style(WHITE.mix(0.3))
+            .axis_desc_style(("sans-serif", 15))
+            .draw()?;
+
+        chart.draw_series(bins.iter().map(|bin| {
+            Rectangle::new([(bin.start, 0.0), (bin.end, bin.count as f64)], self.color.filled())
+        }))?;
+
+        // Outline each bar so neighbouring bins stay distinguishable
+        chart.draw_series(bins.iter().map(|bin| {
+            Rectangle::new([(bin.start, 0.0), (bin.end, bin.count as f64)], WHITE.stroke_width(1))
+        }))?;
+
+        root.present()?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bins_only_finite_values() {
+        let chart = HistogramChart::new(vec![1.0, f64::NAN, 2.0, f64::INFINITY, 3.0, f64::NEG_INFINITY])
+            .bins(BinRule::Count(2));
+        let bins = chart.compute_bins();
+        assert_eq!(bins.iter().map(|bin| bin.count).sum::<usize>(), 3);
+        assert_eq!(bins[0].start, 1.0);
+        assert_eq!(bins[1].end, 3.0);
+    }
+
+    #[test]
+    fn fits_ranges_to_bins() {
+        let chart = HistogramChart::new(vec![0.0, 1.0, 1.5, 1.8, 4.0]).bins(BinRule::Width(2.0));
+        let bins = chart.compute_bins();
+        let (x_range, y_range) = chart.ranges(&bins).unwrap();
+        assert_eq!(x_range, bins[0].start..bins[bins.len() - 1].end);
+        assert_eq!(y_range.start, 0.0);
+        assert!(y_range.end >= 4.0);
+    }
+
+    #[test]
+    fn honours_frequency_scale() {
+        let chart = HistogramChart::new(vec![1.0, 2.0, 3.0]).y_scale(AxisScale::fixed(0.0..10.0));
+        let (_, y_range) = chart.ranges(&chart.compute_bins()).unwrap();
+        assert_eq!(y_range, 0.0..10.0);
+    }
+
+    #[test]
+    fn rejects_no_finite_values() {
+        for values in [vec![], vec![f64::NAN, f64::INFINITY]] {
+            let chart = HistogramChart::new(values);
+            assert!(matches!(chart.ranges(&chart.compute_bins()), Err(ChartError::EmptyData(_))));
+        }
+    }
+}
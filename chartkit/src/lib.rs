@@ -0,0 +1,38 @@
+//! Chart builders shared by the bar, histogram, line and scatter crates.
+//!
+//! Each builder owns its data, is configured through chained setters and
+//! renders to a PNG (or GIF for animated 3D scatter plots) with
+//! `generate_chart` / `generate_plot`. Charts can also be described in a
+//! TOML or JSON file and rendered through [`spec::ChartSpec`]. Failures
+//! are reported as a [`ChartError`] naming the file, column or value
+//! involved.
+
+pub mod axis;
+pub mod bar;
+pub mod binning;
+pub mod category;
+pub mod data;
+pub mod dialect;
+pub mod encoding;
+pub mod error;
+pub mod format;
+pub mod histogram;
+pub mod line;
+pub mod scatter;
+pub mod series;
+pub mod spec;
+pub mod table;
+pub mod time;
+
+pub use axis::TickLayout;
+pub use bar::{BarChart, BarMode, GroupedBarChart, LabelPosition, Orientation, ValueLabels};
+pub use dialect::Dialect;
+pub use encoding::Encoding;
+pub use error::ChartError;
+pub use format::{Locale, NumberFormat, NumberStyle};
+pub use histogram::HistogramChart;
+pub use line::{LegendPosition, LineChart, LineMode};
+pub use scatter::{Scatter3d, ScatterPlot};
+pub use series::{DropSeries, SeriesFilter};
+pub use spec::ChartSpec;
+pub use table::{Melt, Pivot, Table};
@@ -0,0 +1,663 @@
+use crate::axis::{draw_x_ticks, AxisScale, CategoryTicks, TickLayout};
+use crate::error::ChartError;
+use crate::format::{axis_label, NumberFormat};
+use crate::time::{date_position, date_ticks, Calendar, TimeUnit};
+use chrono::{Duration, NaiveDate};
+use plotters::element::ComposedElement;
+use plotters::prelude::*;
+use plotters::style::text_anchor::{HPos, Pos, VPos};
+use serde::Deserialize;
+use std::ops::Range;
+use std::str::FromStr;
+
+// Most series an `Auto` legend lists inside the plot area.
+const INSIDE_LEGEND_MAX: usize = 8;
+
+const LEGEND_FONT_SIZE: u32 = 20;
+// Height of a legend entry, and the gap around and between entries.
+const LEGEND_ROW: u32 = 30;
+const LEGEND_PAD: u32 = 15;
+// Width of the line swatch in front of each series name.
+const SWATCH_WIDTH: i32 = 30;
+
+/// How a [`LineChart`] draws its series.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum LineMode {
+    /// A line per series.
+    #[default]
+    Line,
+    /// A line per series with the area between it and the baseline filled.
+    Area,
+    /// Filled bands stacked up from the baseline, one per series; missing
+    /// values count as zero.
+    #[serde(alias = "stacked-area")]
+    Stacked,
+    /// Like `Stacked`, with each point scaled to 100% of its total.
+    PercentStacked,
+    /// Like `Stacked`, centered on zero: a streamgraph.
+    #[serde(alias = "streamgraph")]
+    Stream,
+}
+
+impl LineMode {
+    fn stacked(&self) -> bool {
+        matches!(self, LineMode::Stacked | LineMode::PercentStacked | LineMode::Stream)
+    }
+}
+
+impl FromStr for LineMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "line" => Ok(LineMode::Line),
+            "area" => Ok(LineMode::Area),
+            "stacked" | "stacked-area" => Ok(LineMode::Stacked),
+            "percent-stacked" | "percent" => Ok(LineMode::PercentStacked),
+            "stream" | "streamgraph" => Ok(LineMode::Stream),
+            _ => Err(format!(
+                "invalid line mode '{}', expected line, area, stacked, percent-stacked or stream",
+                s
+            )),
+        }
+    }
+}
+
+/// Where the legend of a [`LineChart`] is drawn.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum LegendPosition {
+    /// In the lower right corner of the plot area, or outside it for more
+    /// than eight series and for stacked bands, which fill the plot area.
+    #[default]
+    Auto,
+    UpperLeft,
+    UpperRight,
+    LowerLeft,
+    LowerRight,
+    /// To the right of the plot area, in as many columns as it takes.
+    Outside,
+    /// No legend.
+    #[serde(alias = "none")]
+    Hidden,
+}
+
+impl FromStr for LegendPosition {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "auto" => Ok(LegendPosition::Auto),
+            "upper-left" => Ok(LegendPosition::UpperLeft),
+            "upper-right" => Ok(LegendPosition::UpperRight),
+            "lower-left" => Ok(LegendPosition::LowerLeft),
+            "lower-right" => Ok(LegendPosition::LowerRight),
+            "outside" => Ok(LegendPosition::Outside),
+            "hidden" | "none" => Ok(LegendPosition::Hidden),
+            _ => Err(format!(
+                "invalid legend position '{}', expected auto, upper-left, upper-right, lower-left, lower-right, outside or none",
+                s
+            )),
+        }
+    }
+}
+
+/// A multi-series line chart over a shared categorical x-axis, or over a
+/// continuous time axis when the points are given dates.
+pub struct LineChart {
+    x_data: Vec<String>,
+    dates: Option<Vec<NaiveDate>>,
+    time_unit: Option<TimeUnit>,
+    calendar: Calendar,
+    series: Vec<(String, Vec<f64>)>,
+    caption: String,
+    size: (u32, u32),
+    x_desc: String,
+    y_desc: String,
+    y_scale: AxisScale,
+    y_labels: usize,
+    y_format: Option<NumberFormat>,
+    x_tick_layout: TickLayout,
+    markers: bool,
+    legend: LegendPosition,
+    mode: LineMode,
+    fill_opacity: Option<f64>,
+    baseline: f64,
+}
+
+impl LineChart {
+    pub fn new(x_data: Vec<String>) -> Self {
+        LineChart {
+            x_data,
+            dates: None,
+            time_unit: None,
+            calendar: Calendar::default(),
+            series: Vec::new(),
+            caption: String::new(),
+            size: (1600, 1200),
+            x_desc: String::new(),
+            y_desc: String::new(),
+            y_scale: AxisScale::default(),
+            y_labels: 25,
+            y_format: None,
+            x_tick_layout: TickLayout::default(),
+            markers: true,
+            legend: LegendPosition::default(),
+            mode: LineMode::default(),
+            fill_opacity: None,
+            baseline: 0.0,
+        }
+    }
+
+    /// Places the points by date, one date per x category, on a continuous
+    /// time axis instead of evenly spaced categories.
+    pub fn dates(mut self, dates: Vec<NaiveDate>) -> Self {
+        self.dates = Some(dates);
+        self
+    }
+
+    /// Tick spacing of the time axis; picked from the date span by default.
+    pub fn time_unit(mut self, unit: TimeUnit) -> Self {
+        self.time_unit = Some(unit);
+        self
+    }
+
+    /// Calendar the time axis labels years in.
+    pub fn calendar(mut self, calendar: Calendar) -> Self {
+        self.calendar = calendar;
+        self
+    }
+
+    /// Adds a series with one value per x category.
+    pub fn series(mut self, name: &str, values: Vec<f64>) -> Self {
+        self.series.push((name.to_string(), values));
+        self
+    }
+
+    pub fn caption(mut self, caption: &str) -> Self {
+        self.caption = caption.to_string();
+        self
+    }
+
+    pub fn size(mut self, width: u32, height: u32) -> Self {
+        self.size = (width, height);
+        self
+    }
+
+    pub fn x_desc(mut self, desc: &str) -> Self {
+        self.x_desc = desc.to_string();
+        self
+    }
+
+    pub fn y_desc(mut self, desc: &str) -> Self {
+        self.y_desc = desc.to_string();
+        self
+    }
+
+    /// Pins the y-axis to `range` instead of fitting it to the data.
+    pub fn y_range(mut self, range: Range<f64>) -> Self {
+        self.y_scale = AxisScale::fixed(range);
+        self
+    }
+
+    pub fn y_scale(mut self, scale: AxisScale) -> Self {
+        self.y_scale = scale;
+        self
+    }
+
+    pub fn y_labels(mut self, count: usize) -> Self {
+        self.y_labels = count;
+        self
+    }
+
+    /// Writes the y tick labels in `format`, e.g. with thousands
+    /// separators or SI suffixes.
+    pub fn y_format(mut self, format: NumberFormat) -> Self {
+        self.y_format = Some(format);
+        self
+    }
+
+    /// How category labels are fitted when they are too long to sit side by
+    /// side; chosen from the label widths by default.
+    pub fn x_tick_layout(mut self, layout: TickLayout) -> Self {
+        self.x_tick_layout = layout;
+        self
+    }
+
+    /// Draws series as lines, areas or stacked bands.
+    pub fn mode(mut self, mode: LineMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Opacity of the area fills, 0.3 for overlapping areas and 0.8 for
+    /// stacked bands by default.
+    pub fn fill_opacity(mut self, opacity: f64) -> Self {
+        self.fill_opacity = Some(opacity);
+        self
+    }
+
+    /// The y value areas are filled down to and stacks start from; 0 by
+    /// default. Percent-stacked and stream charts have fixed baselines.
+    pub fn baseline(mut self, baseline: f64) -> Self {
+        self.baseline = baseline;
+        self
+    }
+
+    /// Draws a circle on every data point of a line or area chart.
+    pub fn markers(mut self, markers: bool) -> Self {
+        self.markers = markers;
+        self
+    }
+
+    /// Where the legend naming the series is drawn.
+    pub fn legend(mut self, legend: LegendPosition) -> Self {
+        self.legend = legend;
+        self
+    }
+
+    // The legend position with `Auto` resolved by the series count.
+    fn legend_position(&self) -> LegendPosition {
+        match self.legend {
+            LegendPosition::Auto if self.series.len() > INSIDE_LEGEND_MAX || self.mode.stacked() => {
+                LegendPosition::Outside
+            }
+            LegendPosition::Auto => LegendPosition::LowerRight,
+            position => position,
+        }
+    }
+
+    // The line, marker and fill styles of series `index`; there is no fill
+    // in line mode.
+    fn series_styles(&self, index: usize) -> (ShapeStyle, ShapeStyle, Option<ShapeStyle>) {
+        let color = Palette99::pick(index);
+        let opacity = self.fill_opacity.unwrap_or(if self.mode.stacked() { 0.8 } else { 0.3 });
+        let fill = match self.mode {
+            LineMode::Line => None,
+            _ => Some(color.mix(opacity).filled()),
+        };
+        (
+            ShapeStyle::from(&color).filled().stroke_width(2),
+            ShapeStyle::from(&color).filled(),
+            fill,
+        )
+    }
+
+    // The band `(start, end)` each series covers at every x: from the
+    // baseline to the value, or its slice of the stack, with the series'
+    // line along `end`. Positive values stack up from the baseline and
+    // negative ones down from it, so bands never overlap.
+    fn bands(&self) -> Vec<Vec<(f64, f64)>> {
+        let count = self.x_data.len();
+        let value = |values: &[f64], i: usize| values.get(i).cloned().unwrap_or(f64::NAN);
+        if !self.mode.stacked() {
+            return self
+                .series
+                .iter()
+                .map(|(_, values)| (0..count).map(|i| (self.baseline, value(values, i))).collect())
+                .collect();
+        }
+
+        let mut bands = vec![Vec::with_capacity(count); self.series.len()];
+        for i in 0..count {
+            let values: Vec<f64> = self
+                .series
+                .iter()
+                .map(|(_, values)| Some(value(values, i)).filter(|v| v.is_finite()).unwrap_or(0.0))
+                .collect();
+            let total: f64 = values.iter().sum();
+            let (scale, base) = match self.mode {
+                LineMode::PercentStacked => {
+                    let magnitude: f64 = values.iter().map(|v| v.abs()).sum();
+                    (if magnitude > 0.0 { 100.0 / magnitude } else { 0.0 }, 0.0)
+                }
+                // Centered: the stack reaches as far below zero as above
+                LineMode::Stream => (1.0, -total / 2.0),
+                _ => (1.0, self.baseline),
+            };
+            let (mut up, mut down) = (base, base);
+            for (band, value) in bands.iter_mut().zip(values) {
+                let height = value * scale;
+                if height < 0.0 {
+                    band.push((down, down + height));
+                    down += height;
+                } else {
+                    band.push((up, up + height));
+                    up += height;
+                }
+            }
+        }
+        bands
+    }
+
+    // The y range showing every band: 0-100% for percent stacks, reaching
+    // down to -100% when a value is negative.
+    fn fit_y(&self, bands: &[Vec<(f64, f64)>]) -> Range<f64> {
+        match self.mode {
+            LineMode::Line => self
+                .y_scale
+                .fit(self.series.iter().flat_map(|(_, values)| values.iter().cloned())),
+            LineMode::PercentStacked => {
+                let has_negative = bands.iter().flatten().any(|&(start, end)| start.min(end) < 0.0);
+                if has_negative { -100.0..100.0 } else { 0.0..100.0 }
+            }
+            _ => self
+                .y_scale
+                .fit(bands.iter().flatten().flat_map(|&(start, end)| [start, end])),
+        }
+    }
+
+    pub fn generate_chart(&self, output_path: &str) -> Result<(), ChartError> {
+        if self.x_data.is_empty() || self.series.is_empty() {
+            return Err(ChartError::EmptyData("line chart has no points or series".to_string()));
+        }
+        let root = BitMapBackend::new(output_path, self.size).into_drawing_area();
+        root.fill(&WHITE)?;
+
+        // An outside legend takes a strip on the right, one column per
+        // `rows` series. The rows fit the height left by the caption, the
+        // margins and the smallest x label area.
+        let legend = self.legend_position();
+        let legend_style = TextStyle::from(("sans-serif", LEGEND_FONT_SIZE));
+        let rows = (self.size.1.saturating_sub(300 + 2 * LEGEND_PAD) / LEGEND_ROW).max(1) as usize;
+        let (column_width, legend_width) = match legend {
+            LegendPosition::Outside => {
+                let mut name_width = 0;
+                for (name, _) in &self.series {
+                    name_width = name_width.max(root.estimate_text_size(name, &legend_style)?.0);
+                }
+                let column_width = SWATCH_WIDTH as u32 + 10 + name_width + LEGEND_PAD;
+                let columns = self.series.len().div_ceil(rows) as u32;
+                (column_width, columns * column_width + 3 * LEGEND_PAD)
+            }
+            _ => (0, 0),
+        };
+        let plot_width = self.size.0.saturating_sub(legend_width);
+        let (plot_area, _) = root.split_horizontally(plot_width);
+
+        // X positions: category indices, or days on a time axis
+        let positions: Vec<f64> = match &self.dates {
+            Some(dates) => dates.iter().map(|&date| date_position(date)).collect(),
+            None => (0..self.x_data.len()).map(|i| i as f64).collect(),
+        };
+        let x_min = positions.iter().cloned().fold(f64::INFINITY, f64::min);
+        let x_max = positions.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+        // A single x value sits in the middle: half a month either side on a
+        // time axis, half a category otherwise
+        let x_range = match (x_min < x_max, &self.dates) {
+            (true, _) => x_min..x_max,
+            (false, Some(_)) => x_min - 15.0..x_min + 15.0,
+            (false, None) => x_min - 0.5..x_min + 0.5,
+        };
+        // Points in x order, so out-of-order dated rows still draw one line
+        let mut order: Vec<usize> = (0..positions.len()).collect();
+        order.sort_by(|&a, &b| positions[a].partial_cmp(&positions[b]).unwrap());
+
+        let bands = self.bands();
+        let y_range = self.fit_y(&bands);
+
+        // Category labels sit one per point, laid out for the plot width
+        let ticks = match self.dates {
+            Some(_) => None,
+            None => {
+                let slot = plot_width.saturating_sub(210) as f64
+                    / self.x_data.len().saturating_sub(1).max(1) as f64;
+                Some(CategoryTicks::plan(&root, &self.x_data, slot, self.x_tick_layout, 0)?)
+            }
+        };
+        let desc_height = if self.x_desc.is_empty() { 0 } else { 50 };
+        let x_label_area = match &ticks {
+            Some(ticks) => (ticks.height + desc_height).max(100),
+            None => 100,
+        };
+
+        let mut chart = ChartBuilder::on(&plot_area)
+            .caption(&self.caption, ("sans-serif", 60))
+            .x_label_area_size(x_label_area)
+            .y_label_area_size(100)
+            .margin(55)
+            .build_cartesian_2d(x_range, y_range)?;
+
+        let percent = self.mode == LineMode::PercentStacked;
+        chart
+            .configure_mesh()
+            .x_labels(0)
+            .y_labels(self.y_labels)
+            .y_label_formatter(&|y| match &self.y_format {
+                None if percent => format!("{}%", y),
+                format => axis_label(format.as_ref(), *y),
+            })
+            .x_desc(&self.x_desc)
+            .y_desc(&self.y_desc)
+            .axis_desc_style(TextStyle::from(("sans-serif", 40)))
+            .draw()?;
+
+        if let Some(ticks) = &ticks {
+            ticks.draw(&root, &chart, &positions)?;
+        }
+        if let Some(dates) = &self.dates {
+            if let (Some(&start), Some(&end)) = (dates.iter().min(), dates.iter().max()) {
+                // Ticks span the padding around a single date
+                let (start, end) = match start == end {
+                    true => (start - Duration::days(15), end + Duration::days(15)),
+                    false => (start, end),
+                };
+                let unit = self.time_unit.unwrap_or_else(|| TimeUnit::for_span(start, end));
+                draw_x_ticks(&root, &mut chart, &date_ticks(start, end, unit, self.calendar))?;
+            }
+        }
+
+        // Fills go under every line, so a later area never hides a line
+        for (index, band) in bands.iter().enumerate() {
+            let Some(fill_style) = self.series_styles(index).2 else {
+                continue;
+            };
+            let points: Vec<(f64, (f64, f64))> = order.iter().map(|&i| (positions[i], band[i])).collect();
+            for segment in points.split(|(_, (_, end))| !end.is_finite()) {
+                let outline = segment
+                    .iter()
+                    .map(|&(x, (_, end))| (x, end))
+                    .chain(segment.iter().rev().map(|&(x, (start, _))| (x, start)));
+                chart.draw_series(std::iter::once(Polygon::new(outline.collect::<Vec<_>>(), fill_style)))?;
+            }
+        }
+
+        for (index, ((name, _), band)) in self.series.iter().zip(&bands).enumerate() {
+            let (line_style, point_style, fill_style) = self.series_styles(index);
+            // Stacked bands are too dense for markers
+            let markers = self.markers && !self.mode.stacked();
+            let marker_style = markers.then_some(point_style);
+            let points: Vec<(f64, f64)> = order.iter().map(|&i| (positions[i], band[i].1)).collect();
+
+            // Missing values break the line rather than dropping to zero
+            let segments = points
+                .split(|(_, y)| !y.is_finite())
+                .flat_map(|segment| LineSeries::new(segment.to_vec(), line_style));
+            chart
+                .draw_series(segments)?
+                .label(name.as_str())
+                .legend(move |at| swatch(at, line_style, fill_style, marker_style));
+
+            if markers {
+                chart.draw_series(
+                    points
+                        .into_iter()
+                        .filter(|(_, y)| y.is_finite())
+                        .map(|(x, y)| Circle::new((x, y), 5, point_style)),
+                )?;
+            }
+        }
+
+        // The legend is drawn once, after every series has its entry
+        let inside = match legend {
+            LegendPosition::UpperLeft => Some(SeriesLabelPosition::UpperLeft),
+            LegendPosition::UpperRight => Some(SeriesLabelPosition::UpperRight),
+            LegendPosition::LowerLeft => Some(SeriesLabelPosition::LowerLeft),
+            LegendPosition::LowerRight => Some(SeriesLabelPosition::LowerRight),
+            _ => None,
+        };
+        if let Some(position) = inside {
+            chart
+                .configure_series_labels()
+                .background_style(WHITE.mix(0.8))
+                .position(position)
+                .legend_area_size(SWATCH_WIDTH as u32 + 10)
+                .label_font(legend_style)
+                .border_style(BLACK)
+                .draw()?;
+        } else if legend == LegendPosition::Outside {
+            let left = plot_width as i32 + LEGEND_PAD as i32;
+            let top = chart.plotting_area().get_pixel_range().1.start;
+            let name_style = legend_style.pos(Pos::new(HPos::Left, VPos::Center));
+            for (index, (name, _)) in self.series.iter().enumerate() {
+                let (column, row) = ((index / rows) as i32, (index % rows) as i32);
+                let x = left + LEGEND_PAD as i32 + column * column_width as i32;
+                let y = top + (LEGEND_PAD + LEGEND_ROW / 2) as i32 + row * LEGEND_ROW as i32;
+                let (line_style, point_style, fill_style) = self.series_styles(index);
+                let marker_style = (self.markers && !self.mode.stacked()).then_some(point_style);
+                root.draw(&swatch((x, y), line_style, fill_style, marker_style))?;
+                root.draw(&Text::new(name.clone(), (x + SWATCH_WIDTH + 10, y), name_style.clone()))?;
+            }
+            let used_rows = self.series.len().min(rows) as u32;
+            let bottom = top + (used_rows * LEGEND_ROW + 2 * LEGEND_PAD) as i32;
+            let right = self.size.0 as i32 - LEGEND_PAD as i32;
+            root.draw(&Rectangle::new([(left, top), (right, bottom)], BLACK))?;
+        }
+
+        root.present()?;
+
+        Ok(())
+    }
+}
+
+// A filled box under a line with a marker on it, in pixel coordinates.
+type Swatch<DB> = ComposedElement<
+    (i32, i32),
+    DB,
+    Rectangle<(i32, i32)>,
+    ComposedElement<(i32, i32), DB, PathElement<(i32, i32)>, Circle<(i32, i32), u32>>,
+>;
+
+// A legend swatch at `at`: a stroke in a series' line style over its area
+// fill, with its marker in the middle when markers are drawn.
+fn swatch<DB: DrawingBackend>(
+    at: (i32, i32),
+    line_style: ShapeStyle,
+    fill_style: Option<ShapeStyle>,
+    marker_style: Option<ShapeStyle>,
+) -> Swatch<DB> {
+    let fill_style = fill_style.unwrap_or_else(|| TRANSPARENT.filled());
+    let (marker_style, radius) = match marker_style {
+        Some(style) => (style, 5),
+        None => (line_style, 0),
+    };
+    EmptyElement::at(at)
+        + Rectangle::new([(0, 0), (SWATCH_WIDTH, 10)], fill_style)
+        + PathElement::new(vec![(0, 0), (SWATCH_WIDTH, 0)], line_style)
+        + Circle::new((SWATCH_WIDTH / 2, 0), radius, marker_style)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chart(mode: LineMode, series: &[&[f64]]) -> LineChart {
+        let count = series.iter().map(|values| values.len()).max().unwrap_or(0);
+        let mut chart = LineChart::new((0..count).map(|i| format!("Q{}", i + 1)).collect()).mode(mode);
+        for (index, values) in series.iter().enumerate() {
+            chart = chart.series(&format!("s{}", index), values.to_vec());
+        }
+        chart
+    }
+
+    #[test]
+    fn lines_and_areas_start_from_the_baseline() {
+        let bands = chart(LineMode::Area, &[&[5.0, -3.0]]).baseline(1.0).bands();
+        assert_eq!(bands, vec![vec![(1.0, 5.0), (1.0, -3.0)]]);
+
+        let bands = chart(LineMode::Line, &[&[2.0, 4.0], &[1.0]]).bands();
+        assert!(bands[1][1].1.is_nan());
+    }
+
+    #[test]
+    fn stacks_positive_values_up() {
+        let bands = chart(LineMode::Stacked, &[&[5.0, 1.0], &[3.0, 2.0]]).bands();
+        assert_eq!(bands, vec![vec![(0.0, 5.0), (0.0, 1.0)], vec![(5.0, 8.0), (1.0, 3.0)]]);
+    }
+
+    #[test]
+    fn stacks_negative_values_down_without_overlap() {
+        let bands = chart(LineMode::Stacked, &[&[5.0], &[-3.0], &[2.0], &[-1.0]]).bands();
+        let at_first: Vec<(f64, f64)> = bands.iter().map(|band| band[0]).collect();
+        assert_eq!(at_first, vec![(0.0, 5.0), (0.0, -3.0), (5.0, 7.0), (-3.0, -4.0)]);
+    }
+
+    #[test]
+    fn stacks_missing_values_as_zero() {
+        let bands = chart(LineMode::Stacked, &[&[f64::NAN, 2.0], &[3.0]]).bands();
+        assert_eq!(bands, vec![vec![(0.0, 0.0), (0.0, 2.0)], vec![(0.0, 3.0), (2.0, 2.0)]]);
+    }
+
+    #[test]
+    fn normalises_percent_stacks_by_magnitude() {
+        let chart = chart(LineMode::PercentStacked, &[&[6.0, 1.0], &[-2.0, 3.0], &[2.0, 0.0]]);
+        let bands = chart.bands();
+        let at_first: Vec<(f64, f64)> = bands.iter().map(|band| band[0]).collect();
+        assert_eq!(at_first, vec![(0.0, 60.0), (0.0, -20.0), (60.0, 80.0)]);
+        assert_eq!(bands[1][1], (25.0, 100.0));
+        assert_eq!(chart.fit_y(&bands), -100.0..100.0);
+    }
+
+    #[test]
+    fn keeps_positive_percent_stacks_above_zero() {
+        let chart = chart(LineMode::PercentStacked, &[&[1.0, 0.0], &[3.0, 0.0]]);
+        let bands = chart.bands();
+        assert_eq!(bands, vec![vec![(0.0, 25.0), (0.0, 0.0)], vec![(25.0, 100.0), (0.0, 0.0)]]);
+        assert_eq!(chart.fit_y(&bands), 0.0..100.0);
+    }
+
+    #[test]
+    fn centers_streams_on_zero() {
+        let bands = chart(LineMode::Stream, &[&[4.0], &[2.0]]).bands();
+        assert_eq!(bands, vec![vec![(-3.0, 1.0)], vec![(1.0, 3.0)]]);
+
+        // With mixed signs the whole stack is shifted by half its net total
+        let bands = chart(LineMode::Stream, &[&[4.0], &[-2.0]]).bands();
+        assert_eq!(bands, vec![vec![(-1.0, 3.0)], vec![(-1.0, -3.0)]]);
+    }
+
+    #[test]
+    fn places_auto_legends_inside_until_crowded() {
+        let few = chart(LineMode::Line, &[&[1.0][..]; 8]);
+        assert_eq!(few.legend_position(), LegendPosition::LowerRight);
+        let many = chart(LineMode::Line, &[&[1.0][..]; 9]);
+        assert_eq!(many.legend_position(), LegendPosition::Outside);
+        let stacked = chart(LineMode::Stacked, &[&[1.0]]);
+        assert_eq!(stacked.legend_position(), LegendPosition::Outside);
+    }
+
+    #[test]
+    fn keeps_chosen_legend_positions() {
+        let stream = chart(LineMode::Stream, &[&[1.0][..]; 12]).legend(LegendPosition::UpperLeft);
+        assert_eq!(stream.legend_position(), LegendPosition::UpperLeft);
+        let hidden = chart(LineMode::Line, &[&[1.0]]).legend(LegendPosition::Hidden);
+        assert_eq!(hidden.legend_position(), LegendPosition::Hidden);
+    }
+
+    #[test]
+    fn parses_modes_and_legend_positions() {
+        assert_eq!("streamgraph".parse(), Ok(LineMode::Stream));
+        assert_eq!("Percent".parse(), Ok(LineMode::PercentStacked));
+        assert!("pie".parse::<LineMode>().is_err());
+        assert_eq!("none".parse(), Ok(LegendPosition::Hidden));
+        assert_eq!(" upper-right".parse(), Ok(LegendPosition::UpperRight));
+        assert!("top".parse::<LegendPosition>().is_err());
+    }
+
+    #[test]
+    fn fills_only_area_modes() {
+        assert!(chart(LineMode::Line, &[&[1.0]]).series_styles(0).2.is_none());
+        assert!(chart(LineMode::Area, &[&[1.0]]).series_styles(0).2.is_some());
+    }
+}
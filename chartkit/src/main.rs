@@ -0,0 +1,636 @@
+use chartkit::binning::BinRule;
+use chartkit::category::{CategoryFilter, SortOrder};
+use chartkit::data::{Column, MissingPolicy};
+use chartkit::dialect::{parse_char, Dialect};
+use chartkit::format::{Locale, NumberFormat};
+use chartkit::spec::{AxesSpec, AxisType, ChartKind, ColumnBindings, DataSource, StyleSpec};
+use chartkit::time::{Calendar, TimeUnit};
+use chartkit::{
+    BarMode, ChartError, ChartSpec, DropSeries, Encoding, LabelPosition, LegendPosition, LineMode,
+    Melt, Orientation, Pivot, SeriesFilter, TickLayout, ValueLabels,
+};
+use clap::{Args, Parser, Subcommand};
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+/// Render charts from CSV files.
+///
+/// Columns are given by header name, or by zero-based index when no header
+/// matches.
+#[derive(Parser)]
+#[command(name = "chartkit", version)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// One bar per category
+    Bar {
+        #[command(flatten)]
+        common: CommonArgs,
+        #[command(flatten)]
+        time: TimeArgs,
+        #[command(flatten)]
+        labels: LabelArgs,
+        /// Category column
+        #[arg(long)]
+        x: Column,
+        /// Value column
+        #[arg(long)]
+        y: Column,
+        /// Categories down the y-axis with bars extending right
+        #[arg(long)]
+        horizontal: bool,
+        /// original, value, value-asc, label or label-desc
+        #[arg(long)]
+        sort: Option<SortOrder>,
+        /// Keep only the first N categories after sorting
+        #[arg(long)]
+        top: Option<usize>,
+        /// Sum the categories cut by --top into one bar, labeled "Other" by default
+        #[arg(long, num_args = 0..=1, default_missing_value = "Other")]
+        other: Option<String>,
+    },
+    /// Side-by-side bars per category, one per value column or group value
+    GroupedBar {
+        #[command(flatten)]
+        common: CommonArgs,
+        /// Category column
+        #[arg(long)]
+        x: Column,
+        /// Value columns or patterns (N*, E1..E10, /regex/), comma
+        /// separated; a single column with --group
+        #[arg(long, value_delimiter = ',', required = true)]
+        y: Vec<Column>,
+        #[command(flatten)]
+        series: SeriesArgs,
+        /// Column naming each row's group, for long-format data
+        #[arg(long)]
+        group: Option<Column>,
+        #[command(flatten)]
+        labels: LabelArgs,
+        /// grouped, stacked or percent-stacked
+        #[arg(long)]
+        mode: Option<BarMode>,
+    },
+    /// One line per value column
+    Line {
+        #[command(flatten)]
+        common: CommonArgs,
+        #[command(flatten)]
+        time: TimeArgs,
+        /// X-axis label column
+        #[arg(long)]
+        x: Column,
+        /// Value columns or patterns (N*, E1..E10, /regex/), comma separated
+        #[arg(long, value_delimiter = ',', required = true)]
+        y: Vec<Column>,
+        #[command(flatten)]
+        series: SeriesArgs,
+        /// Legend placement: auto, upper-left, upper-right, lower-left,
+        /// lower-right, outside or none
+        #[arg(long)]
+        legend: Option<LegendPosition>,
+        /// line, area, stacked, percent-stacked or stream
+        #[arg(long)]
+        mode: Option<LineMode>,
+        /// Opacity of the area fills, from 0 to 1
+        #[arg(long)]
+        opacity: Option<f64>,
+        /// Y value areas are filled down to and stacks start from
+        #[arg(long, allow_hyphen_values = true)]
+        baseline: Option<f64>,
+    },
+    /// Frequency histogram of a numeric column
+    Histogram {
+        #[command(flatten)]
+        common: CommonArgs,
+        /// Value column
+        #[arg(long)]
+        x: Column,
+        /// Bin count, or sturges, scott, fd, width:<w>
+        #[arg(long)]
+        bins: Option<BinRule>,
+    },
+    /// 2D scatter plot, optionally colored by a group column
+    Scatter {
+        #[command(flatten)]
+        common: CommonArgs,
+        #[arg(long)]
+        x: Column,
+        #[arg(long)]
+        y: Column,
+        /// Column whose values color the points
+        #[arg(long)]
+        group: Option<Column>,
+    },
+    /// 3D scatter plot rendered to a GIF
+    Scatter3d {
+        #[command(flatten)]
+        common: CommonArgs,
+        #[arg(long)]
+        x: Column,
+        #[arg(long)]
+        y: Column,
+        #[arg(long)]
+        z: Column,
+        /// Number of animation frames
+        #[arg(long)]
+        frames: Option<u32>,
+    },
+    /// Render one or more TOML/JSON chart spec files
+    Render {
+        #[arg(required = true)]
+        specs: Vec<PathBuf>,
+    },
+}
+
+#[derive(Args)]
+struct CommonArgs {
+    /// CSV file to read
+    #[arg(long)]
+    input: PathBuf,
+    /// Image file to write
+    #[arg(long)]
+    output: PathBuf,
+    /// Chart caption
+    #[arg(long, default_value = "")]
+    title: String,
+    /// Canvas size as WIDTHxHEIGHT, e.g. 1200x800
+    #[arg(long, value_parser = parse_size)]
+    size: Option<(u32, u32)>,
+    #[arg(long)]
+    x_desc: Option<String>,
+    #[arg(long)]
+    y_desc: Option<String>,
+    /// Category label layout of bar and line charts: auto, horizontal,
+    /// wrap, rotate45 or rotate90
+    #[arg(long)]
+    x_tick_layout: Option<TickLayout>,
+    /// Numeric x tick labels: plain, thousands, si, percent or currency,
+    /// optionally with a decimal count, e.g. fixed:2
+    #[arg(long)]
+    x_format: Option<NumberFormat>,
+    /// Numeric y tick labels, as for --x-format
+    #[arg(long)]
+    y_format: Option<NumberFormat>,
+    /// Digit grouping and decimal mark of the formats, e.g. en, th, de or fr
+    #[arg(long)]
+    locale: Option<Locale>,
+    /// Empty or non-numeric values: skip (the record), zero, gap or fail
+    #[arg(long, default_value = "skip")]
+    missing: MissingPolicy,
+    /// Input encoding: auto (detected), utf-8, utf-16le, utf-16be or tis-620
+    #[arg(long, default_value = "auto")]
+    encoding: Encoding,
+    /// Stream the input instead of holding it in memory (true or false);
+    /// by default only files over 64 MiB are streamed
+    #[arg(long)]
+    stream: Option<bool>,
+    #[command(flatten)]
+    dialect: DialectArgs,
+    #[command(flatten)]
+    reshape: ReshapeArgs,
+}
+
+#[derive(Args)]
+struct DialectArgs {
+    /// Field separator, e.g. ";" or tab; detected when unset
+    #[arg(long, value_parser = parse_char)]
+    delimiter: Option<char>,
+    #[arg(long, value_parser = parse_char, default_value = "\"")]
+    quote: char,
+    /// Character escaping quotes inside quoted fields, e.g. \; quotes
+    /// are escaped by doubling them otherwise
+    #[arg(long, value_parser = parse_char)]
+    escape: Option<char>,
+    /// Ignore lines starting with this character, e.g. "#"
+    #[arg(long, value_parser = parse_char)]
+    comment: Option<char>,
+    /// Zero-based line of the header row; the lines above it are skipped
+    #[arg(long, default_value_t = 0)]
+    header_row: usize,
+    /// Records to skip after the header
+    #[arg(long, default_value_t = 0)]
+    skip_rows: usize,
+    /// Records to skip at the end of the file
+    #[arg(long, default_value_t = 0)]
+    skip_footer: usize,
+}
+
+impl DialectArgs {
+    fn into_dialect(self) -> Dialect {
+        Dialect {
+            delimiter: self.delimiter,
+            quote: self.quote,
+            escape: self.escape,
+            comment: self.comment,
+            header_row: self.header_row,
+            skip_rows: self.skip_rows,
+            skip_footer: self.skip_footer,
+        }
+    }
+}
+
+#[derive(Args)]
+struct ReshapeArgs {
+    /// Melt the input long, keeping these columns on every record, comma separated
+    #[arg(long, value_delimiter = ',')]
+    melt_id: Vec<Column>,
+    /// Columns or patterns to melt, comma separated; all but --melt-id by default
+    #[arg(long, value_delimiter = ',')]
+    melt_columns: Vec<Column>,
+    /// Name of the melted column holding the former column names
+    #[arg(long, default_value = "variable")]
+    melt_variable: String,
+    /// Name of the melted column holding the values
+    #[arg(long, default_value = "value")]
+    melt_value: String,
+    /// Pivot the input wide, one record per value of this column
+    #[arg(long, requires_all = ["pivot_columns", "pivot_values"])]
+    pivot_index: Option<Column>,
+    /// Column whose values name the pivoted columns
+    #[arg(long, requires = "pivot_index")]
+    pivot_columns: Option<Column>,
+    /// Column holding the pivoted values
+    #[arg(long, requires = "pivot_index")]
+    pivot_values: Option<Column>,
+}
+
+impl ReshapeArgs {
+    fn into_reshape(self) -> (Option<Melt>, Option<Pivot>) {
+        let melt = (!self.melt_id.is_empty() || !self.melt_columns.is_empty()).then_some(Melt {
+            id: self.melt_id,
+            columns: self.melt_columns,
+            variable: self.melt_variable,
+            value: self.melt_value,
+        });
+        let pivot = match (self.pivot_index, self.pivot_columns, self.pivot_values) {
+            (Some(index), Some(columns), Some(values)) => Some(Pivot { index, columns, values }),
+            _ => None,
+        };
+        (melt, pivot)
+    }
+}
+
+#[derive(Args)]
+struct TimeArgs {
+    /// Treat the x column as dates on a continuous time axis
+    #[arg(long)]
+    x_time: bool,
+    /// chrono format of the x dates, e.g. "%b %Y"; may be repeated
+    #[arg(long)]
+    date_format: Vec<String>,
+    /// Tick spacing of the time axis: month, quarter or year
+    #[arg(long)]
+    time_unit: Option<TimeUnit>,
+    /// Calendar of the time axis labels: gregorian, buddhist or both
+    #[arg(long)]
+    calendar: Option<Calendar>,
+}
+
+impl TimeArgs {
+    fn apply(self, spec: &mut ChartSpec) {
+        if self.x_time {
+            spec.axes.x_type = AxisType::Time;
+        }
+        spec.axes.date_formats = self.date_format;
+        spec.axes.time_unit = self.time_unit;
+        spec.axes.calendar = self.calendar;
+    }
+}
+
+#[derive(Args)]
+struct LabelArgs {
+    /// Write each bar's value: outside (default), inside or center
+    #[arg(long, num_args = 0..=1, default_missing_value = "outside")]
+    value_labels: Option<LabelPosition>,
+    /// Number format of the value labels, as for --y-format; thousands by default
+    #[arg(long)]
+    label_format: Option<NumberFormat>,
+    /// Decimals of the value labels
+    #[arg(long)]
+    label_decimals: Option<usize>,
+}
+
+impl LabelArgs {
+    fn apply(self, spec: &mut ChartSpec, locale: Option<Locale>) {
+        spec.style.value_labels = self.value_labels.map(|position| {
+            let mut labels = ValueLabels { position, ..ValueLabels::default() };
+            if let Some(format) = self.label_format {
+                labels.format = format;
+            }
+            if let Some(decimals) = self.label_decimals {
+                labels.format.decimals = Some(decimals);
+            }
+            if let Some(locale) = locale {
+                labels.format.locale = locale;
+            }
+            labels
+        });
+    }
+}
+
+#[derive(Args)]
+struct SeriesArgs {
+    /// Value columns or patterns to leave out, comma separated
+    #[arg(long, value_delimiter = ',')]
+    exclude: Vec<Column>,
+    /// Leave out series with no numbers (missing) or only zeros (zero)
+    #[arg(long)]
+    drop: Option<DropSeries>,
+}
+
+impl SeriesArgs {
+    fn apply(self, spec: &mut ChartSpec) {
+        spec.series = SeriesFilter { exclude: self.exclude, drop: self.drop.unwrap_or_default() };
+    }
+}
+
+fn parse_size(text: &str) -> Result<(u32, u32), String> {
+    let (width, height) = text
+        .split_once(['x', 'X'])
+        .ok_or_else(|| format!("invalid size '{}', expected WIDTHxHEIGHT", text))?;
+    let parse = |s: &str| {
+        s.trim()
+            .parse::<u32>()
+            .map_err(|_| format!("invalid size '{}', expected WIDTHxHEIGHT", text))
+    };
+    Ok((parse(width)?, parse(height)?))
+}
+
+impl CommonArgs {
+    fn into_spec(self, kind: ChartKind, columns: ColumnBindings) -> ChartSpec {
+        let locale = self.locale;
+        let localized = |format: Option<NumberFormat>| match (format, locale) {
+            (Some(format), Some(locale)) => Some(format.locale(locale)),
+            (format, _) => format,
+        };
+        let (melt, pivot) = self.reshape.into_reshape();
+        ChartSpec {
+            kind,
+            title: self.title,
+            output: self.output,
+            size: self.size,
+            data: DataSource {
+                path: self.input,
+                missing: self.missing,
+                encoding: self.encoding,
+                stream: self.stream,
+                melt,
+                pivot,
+                dialect: self.dialect.into_dialect(),
+            },
+            columns,
+            axes: AxesSpec {
+                x_desc: self.x_desc,
+                y_desc: self.y_desc,
+                x_tick_layout: self.x_tick_layout,
+                x_format: localized(self.x_format),
+                y_format: localized(self.y_format),
+                ..AxesSpec::default()
+            },
+            categories: CategoryFilter::default(),
+            series: SeriesFilter::default(),
+            style: StyleSpec::default(),
+        }
+    }
+}
+
+fn render(spec: &ChartSpec) -> Result<(), ChartError> {
+    spec.render()?;
+    println!("Result has been saved to {}", spec.output.display());
+    Ok(())
+}
+
+impl Command {
+    // The charts the command draws: the one a chart subcommand describes, or
+    // those of the files given to `render`, all read before any is drawn.
+    fn into_specs(self) -> Result<Vec<ChartSpec>, ChartError> {
+        let spec = match self {
+            Command::Bar { common, time, labels, x, y, horizontal, sort, top, other } => {
+                let locale = common.locale;
+                let mut spec = common.into_spec(
+                    ChartKind::Bar,
+                    ColumnBindings { x: Some(x), y: vec![y], ..ColumnBindings::default() },
+                );
+                time.apply(&mut spec);
+                labels.apply(&mut spec, locale);
+                if horizontal {
+                    spec.style.orientation = Some(Orientation::Horizontal);
+                }
+                spec.categories = CategoryFilter { sort: sort.unwrap_or_default(), top, other };
+                spec
+            }
+            Command::GroupedBar { common, x, y, series, group, labels, mode } => {
+                let locale = common.locale;
+                let mut spec = common.into_spec(
+                    ChartKind::GroupedBar,
+                    ColumnBindings { x: Some(x), y, group, ..ColumnBindings::default() },
+                );
+                series.apply(&mut spec);
+                spec.style.bar_mode = mode;
+                labels.apply(&mut spec, locale);
+                spec
+            }
+            Command::Line { common, time, x, y, series, legend, mode, opacity, baseline } => {
+                let mut spec = common.into_spec(
+                    ChartKind::Line,
+                    ColumnBindings { x: Some(x), y, ..ColumnBindings::default() },
+                );
+                time.apply(&mut spec);
+                series.apply(&mut spec);
+                spec.style.legend = legend;
+                spec.style.line_mode = mode;
+                spec.style.opacity = opacity;
+                spec.style.baseline = baseline;
+                spec
+            }
+            Command::Histogram { common, x, bins } => {
+                let mut spec = common.into_spec(
+                    ChartKind::Histogram,
+                    ColumnBindings { x: Some(x), ..ColumnBindings::default() },
+                );
+                spec.style.bins = bins;
+                spec
+            }
+            Command::Scatter { common, x, y, group } => common.into_spec(
+                ChartKind::Scatter,
+                ColumnBindings { x: Some(x), y: vec![y], group, ..ColumnBindings::default() },
+            ),
+            Command::Scatter3d { common, x, y, z, frames } => {
+                let mut spec = common.into_spec(
+                    ChartKind::Scatter3d,
+                    ColumnBindings { x: Some(x), y: vec![y], z: Some(z), group: None },
+                );
+                spec.style.frames = frames;
+                spec
+            }
+            Command::Render { specs } => {
+                return specs.iter().map(ChartSpec::from_file).collect();
+            }
+        };
+
+        Ok(vec![spec])
+    }
+}
+
+fn run() -> Result<(), ChartError> {
+    for spec in Cli::parse().command.into_specs()? {
+        render(&spec)?;
+    }
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    match run() {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("Error: {}", err);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chartkit::NumberStyle;
+
+    // The spec `args` describe, after the program name.
+    fn spec(args: &[&str]) -> ChartSpec {
+        let args = ["chartkit"].iter().chain(args);
+        Cli::try_parse_from(args).unwrap().command.into_specs().unwrap().remove(0)
+    }
+
+    fn parses(args: &[&str]) -> bool {
+        Cli::try_parse_from(["chartkit"].iter().chain(args)).is_ok()
+    }
+
+    const IO: [&str; 4] = ["--input", "data.csv", "--output", "chart.png"];
+
+    #[test]
+    fn builds_a_bar_spec() {
+        let spec = spec(&[
+            &["bar"][..],
+            &IO,
+            &["--x", "team", "--y", "3", "--title", "Attendance", "--size", "1200x800"],
+            &["--sort", "value", "--top", "5", "--other", "--horizontal"],
+            &["--value-labels", "--label-decimals", "1", "--locale", "de", "--y-format", "si"],
+        ]
+        .concat());
+
+        assert_eq!(spec.kind, ChartKind::Bar);
+        assert_eq!(spec.data.path, PathBuf::from("data.csv"));
+        assert_eq!(spec.output, PathBuf::from("chart.png"));
+        assert_eq!(spec.title, "Attendance");
+        assert_eq!(spec.size, Some((1200, 800)));
+        assert_eq!(spec.columns.x, Some(Column::from("team")));
+        assert_eq!(spec.columns.y, vec![Column::from("3")]);
+        assert_eq!(
+            spec.categories,
+            CategoryFilter { sort: SortOrder::ValueDesc, top: Some(5), other: Some("Other".to_string()) }
+        );
+        assert_eq!(spec.style.orientation, Some(Orientation::Horizontal));
+        assert_eq!(spec.axes.y_format, Some(NumberFormat::new(NumberStyle::Si).locale(Locale::Dot)));
+
+        let labels = spec.style.value_labels.unwrap();
+        assert_eq!(labels.position, LabelPosition::Outside);
+        assert_eq!(labels.format.decimals, Some(1));
+        assert_eq!(labels.format.locale, Locale::Dot);
+    }
+
+    #[test]
+    fn builds_series_specs() {
+        let grouped = spec(&[
+            &["grouped-bar"][..],
+            &IO,
+            &["--x", "month", "--y", "N*,E1..E3", "--exclude", "N2", "--mode", "percent-stacked"],
+        ]
+        .concat());
+        assert_eq!(grouped.kind, ChartKind::GroupedBar);
+        assert_eq!(grouped.columns.y, vec![Column::from("N*"), Column::from("E1..E3")]);
+        assert_eq!(grouped.series.exclude, vec![Column::from("N2")]);
+        assert_eq!(grouped.style.bar_mode, Some(BarMode::PercentStacked));
+
+        let line = spec(&[
+            &["line"][..],
+            &IO,
+            &["--x", "month", "--y", "a", "--x-time", "--legend", "none", "--mode", "stream"],
+            &["--baseline", "-5", "--calendar", "buddhist"],
+        ]
+        .concat());
+        assert_eq!(line.axes.x_type, AxisType::Time);
+        assert_eq!(line.axes.calendar, Some(Calendar::Buddhist));
+        assert_eq!(line.style.legend, Some(LegendPosition::Hidden));
+        assert_eq!(line.style.line_mode, Some(LineMode::Stream));
+        assert_eq!(line.style.baseline, Some(-5.0));
+    }
+
+    #[test]
+    fn builds_histogram_and_scatter_specs() {
+        let histogram = spec(&[&["histogram"][..], &IO, &["--x", "score", "--bins", "width:5"]].concat());
+        assert_eq!(histogram.kind, ChartKind::Histogram);
+        assert_eq!(histogram.style.bins, Some(BinRule::Width(5.0)));
+
+        let scatter = spec(
+            &[&["scatter"][..], &IO, &["--x", "age", "--y", "salary", "--group", "sex"]].concat(),
+        );
+        assert_eq!(scatter.columns.group, Some(Column::from("sex")));
+
+        let scatter3d = spec(
+            &[&["scatter3d"][..], &IO, &["--x", "a", "--y", "b", "--z", "c", "--frames", "20"]].concat(),
+        );
+        assert_eq!(scatter3d.columns.z, Some(Column::from("c")));
+        assert_eq!(scatter3d.style.frames, Some(20));
+    }
+
+    #[test]
+    fn reads_dialect_and_reshape_options() {
+        let spec = spec(&[
+            &["line"][..],
+            &IO,
+            &["--x", "month", "--y", "passengers", "--missing", "gap"],
+            &["--delimiter", ";", "--header-row", "2"],
+            &["--melt-id", "no,month", "--melt-variable", "year", "--melt-value", "passengers"],
+        ]
+        .concat());
+
+        assert_eq!(spec.data.dialect, Dialect { delimiter: Some(';'), header_row: 2, ..Dialect::default() });
+        assert_eq!(spec.data.missing, MissingPolicy::Gap);
+        assert_eq!(
+            spec.data.melt,
+            Some(Melt {
+                id: vec![Column::from("no"), Column::from("month")],
+                columns: Vec::new(),
+                variable: "year".to_string(),
+                value: "passengers".to_string(),
+            })
+        );
+        assert_eq!(spec.data.pivot, None);
+    }
+
+    #[test]
+    fn rejects_invalid_arguments() {
+        let bar = |args: &[&str]| parses(&[&["bar"][..], &IO, &["--x", "a", "--y", "b"], args].concat());
+        assert!(bar(&[]));
+        assert!(!bar(&["--size", "1200"]));
+        assert!(!bar(&["--sort", "size"]));
+        assert!(!bar(&["--missing", "drop"]));
+        assert!(!bar(&["--pivot-index", "month"]));
+        assert!(!parses(&[&["bar"][..], &IO, &["--x", "a"]].concat()));
+        assert!(!parses(&["render"]));
+        assert!(!parses(&["pie"]));
+    }
+
+    #[test]
+    fn parses_sizes() {
+        assert_eq!(parse_size("1200x800"), Ok((1200, 800)));
+        assert_eq!(parse_size("640 X 480"), Ok((640, 480)));
+        for invalid in ["1200", "x800", "1200x", "wide x tall", "-1x5"] {
+            assert!(parse_size(invalid).is_err(), "{}", invalid);
+        }
+    }
+}
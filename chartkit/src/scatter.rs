@@ -0,0 +1,436 @@
+use crate::axis::AxisScale;
+use crate::error::ChartError;
+use crate::format::{axis_label, NumberFormat};
+use plotters::prelude::*;
+use std::ops::Range;
+
+/// A 2D scatter plot, optionally colored by a group label per point.
+pub struct ScatterPlot {
+    x_data: Vec<f64>,
+    y_data: Vec<f64>,
+    groups: Option<Vec<String>>,
+    group_colors: Vec<(String, RGBColor)>,
+    default_color: RGBColor,
+    caption: String,
+    size: (u32, u32),
+    x_desc: String,
+    y_desc: String,
+    x_scale: AxisScale,
+    y_scale: AxisScale,
+    x_labels: usize,
+    y_labels: usize,
+    x_format: Option<NumberFormat>,
+    y_format: Option<NumberFormat>,
+    point_size: u32,
+}
+
+impl ScatterPlot {
+    pub fn new(x_data: Vec<f64>, y_data: Vec<f64>) -> Self {
+        ScatterPlot {
+            x_data,
+            y_data,
+            groups: None,
+            group_colors: Vec::new(),
+            default_color: BLUE,
+            caption: String::new(),
+            size: (1000, 600),
+            x_desc: String::new(),
+            y_desc: String::new(),
+            x_scale: AxisScale::default(),
+            y_scale: AxisScale::default(),
+            x_labels: 10,
+            y_labels: 10,
+            x_format: None,
+            y_format: None,
+            point_size: 5,
+        }
+    }
+
+    /// Assigns a group label to every point. Points whose group has no color
+    /// registered with `group_color` use the default color.
+    pub fn groups(mut self, groups: Vec<String>) -> Self {
+        self.groups = Some(groups);
+        self
+    }
+
+    pub fn group_color(mut self, group: &str, color: RGBColor) -> Self {
+        self.group_colors.push((group.to_string(), color));
+        self
+    }
+
+    pub fn default_color(mut self, color: RGBColor) -> Self {
+        self.default_color = color;
+        self
+    }
+
+    pub fn caption(mut self, caption: &str) -> Self {
+        self.caption = caption.to_string();
+        self
+    }
+
+    pub fn size(mut self, width: u32, height: u32) -> Self {
+        self.size = (width, height);
+        self
+    }
+
+    pub fn x_desc(mut self, desc: &str) -> Self {
+        self.x_desc = desc.to_string();
+        self
+    }
+
+    pub fn y_desc(mut self, desc: &str) -> Self {
+        self.y_desc = desc.to_string();
+        self
+    }
+
+    /// Pins the x-axis to `range` instead of fitting it to the data.
+    pub fn x_range(mut self, range: Range<f64>) -> Self {
+        self.x_scale = AxisScale::fixed(range);
+        self
+    }
+
+    pub fn x_scale(mut self, scale: AxisScale) -> Self {
+        self.x_scale = scale;
+        self
+    }
+
+    /// Pins the y-axis to `range` instead of fitting it to the data.
+    pub fn y_range(mut self, range: Range<f64>) -> Self {
+        self.y_scale = AxisScale::fixed(range);
+        self
+    }
+
+    pub fn y_scale(mut self, scale: AxisScale) -> Self {
+        self.y_scale = scale;
+        self
+    }
+
+    pub fn x_labels(mut self, count: usize) -> Self {
+        self.x_labels = count;
+        self
+    }
+
+    pub fn y_labels(mut self, count: usize) -> Self {
+        self.y_labels = count;
+        self
+    }
+
+    /// Writes the x tick labels in `format`.
+    pub fn x_format(mut self, format: NumberFormat) -> Self {
+        self.x_format = Some(format);
+        self
+    }
+
+    /// Writes the y tick labels in `format`, e.g. with thousands
+    /// separators or SI suffixes.
+    pub fn y_format(mut self, format: NumberFormat) -> Self {
+        self.y_format = Some(format);
+        self
+    }
+
+    pub fn point_size(mut self, size: u32) -> Self {
+        self.point_size = size;
+        self
+    }
+
+    fn color_of(&self, group: &str) -> RGBColor {
+        self.group_colors
+            .iter()
+            .find(|(name, _)| name == group)
+            .map(|(_, color)| *color)
+            .unwrap_or(self.default_color)
+    }
+
+    // The points drawn, with their group: gaps in either column leave the
+    // point out.
+    fn points(&self) -> Vec<(f64, f64, Option<&str>)> {
+        self.x_data
+            .iter()
+            .zip(self.y_data.iter())
+            .enumerate()
+            .filter(|(_, (x, y))| x.is_finite() && y.is_finite())
+            .map(|(i, (&x, &y))| {
+                let group = self.groups.as_ref().and_then(|groups| groups.get(i)).map(String::as_str);
+                (x, y, group)
+            })
+            .collect()
+    }
+
+    // The axis ranges, fitted to the points drawn.
+    fn ranges(&self, points: &[(f64, f64, Option<&str>)]) -> (Range<f64>, Range<f64>) {
+        (
+            self.x_scale.fit(points.iter().map(|&(x, _, _)| x)),
+            self.y_scale.fit(points.iter().map(|&(_, y, _)| y)),
+        )
+    }
+
+    pub fn generate_plot(&self, output_path: &str) -> Result<(), ChartError> {
+        let points = self.points();
+        if points.is_empty() {
+            return Err(ChartError::EmptyData("scatter plot has no points".to_string()));
+        }
+        let (x_range, y_range) = self.ranges(&points);
+        let root = BitMapBackend::new(output_path, self.size).into_drawing_area();
+        root.fill(&WHITE)?;
+
+        let mut chart = ChartBuilder::on(&root)
+            .caption(&self.caption, ("sans-serif", 40))
+            .x_label_area_size(50)
+            .y_label_area_size(70)
+            .margin(20)
+            .build_cartesian_2d(x_range, y_range)?;
+
+        chart
+            .configure_mesh()
+            .x_labels(self.x_labels)
+            .y_labels(self.y_labels)
+            .x_label_formatter(&|x| axis_label(self.x_format.as_ref(), *x))
+            .y_label_formatter(&|y| axis_label(self.y_format.as_ref(), *y))
+            .x_desc(&self.x_desc)
+            .y_desc(&self.y_desc)
+            .axis_desc_style(TextStyle::from(("sans-serif", 20)))
+            .draw()?;
+
+        chart.draw_series(points.iter().map(|&(x, y, group)| {
+            let color = group.map_or(self.default_color, |group| self.color_of(group));
+            Circle::new((x, y), self.point_size, color.filled())
+        }))?;
+
+        if self.groups.is_some() {
+            // One legend entry per registered group
+            for (name, color) in &self.group_colors {
+                let color = *color;
+                chart
+                    .draw_series(std::iter::empty::<Circle<(f64, f64), u32>>())?
+                    .label(name.as_str())
+                    .legend(move |(x, y)| Circle::new((x, y), 5, color.filled()));
+            }
+            chart
+                .configure_series_labels()
+                .background_style(WHITE.mix(0.8))
+                .border_style(BLACK)
+                .label_font(("sans-serif", 20))
+                .draw()?;
+        }
+
+        root.present()?;
+
+        Ok(())
+    }
+}
+
+/// A 3D scatter plot rendered to a GIF, either as a single frame or as an
+/// animation that tilts the camera.
+pub struct Scatter3d {
+    x_data: Vec<f64>,
+    y_data: Vec<f64>,
+    z_data: Vec<f64>,
+    caption: String,
+    size: (u32, u32),
+    x_scale: AxisScale,
+    y_scale: AxisScale,
+    z_scale: AxisScale,
+    frames: u32,
+    frame_delay: u32,
+    pitch: f64,
+    yaw: f64,
+    scale: f64,
+}
+
+impl Scatter3d {
+    pub fn new(x_data: Vec<f64>, y_data: Vec<f64>, z_data: Vec<f64>) -> Self {
+        Scatter3d {
+            x_scale: AxisScale::default(),
+            y_scale: AxisScale::default(),
+            z_scale: AxisScale::default(),
+            x_data,
+            y_data,
+            z_data,
+            caption: String::new(),
+            size: (1000, 800),
+            frames: 1,
+            frame_delay: 100,
+            pitch: 0.15,
+            yaw: 0.35,
+            scale: 0.8,
+        }
+    }
+
+    pub fn caption(mut self, caption: &str) -> Self {
+        self.caption = caption.to_string();
+        self
+    }
+
+    pub fn size(mut self, width: u32, height: u32) -> Self {
+        self.size = (width, height);
+        self
+    }
+
+    /// Pins the x-axis to `range` instead of fitting it to the data.
+    pub fn x_range(mut self, range: Range<f64>) -> Self {
+        self.x_scale = AxisScale::fixed(range);
+        self
+    }
+
+    pub fn x_scale(mut self, scale: AxisScale) -> Self {
+        self.x_scale = scale;
+        self
+    }
+
+    /// Pins the y-axis to `range` instead of fitting it to the data.
+    pub fn y_range(mut self, range: Range<f64>) -> Self {
+        self.y_scale = AxisScale::fixed(range);
+        self
+    }
+
+    pub fn y_scale(mut self, scale: AxisScale) -> Self {
+        self.y_scale = scale;
+        self
+    }
+
+    /// Pins the z-axis to `range` instead of fitting it to the data.
+    pub fn z_range(mut self, range: Range<f64>) -> Self {
+        self.z_scale = AxisScale::fixed(range);
+        self
+    }
+
+    pub fn z_scale(mut self, scale: AxisScale) -> Self {
+        self.z_scale = scale;
+        self
+    }
+
+    /// Number of animation frames. With more than one frame the pitch sweeps
+    /// up to 90 degrees and back instead of using the fixed `pitch`.
+    pub fn frames(mut self, frames: u32) -> Self {
+        self.frames = frames.max(1);
+        self
+    }
+
+    /// Delay between animation frames in milliseconds.
+    pub fn frame_delay(mut self, delay: u32) -> Self {
+        self.frame_delay = delay;
+        self
+    }
+
+    pub fn pitch(mut self, pitch: f64) -> Self {
+        self.pitch = pitch;
+        self
+    }
+
+    pub fn yaw(mut self, yaw: f64) -> Self {
+        self.yaw = yaw;
+        self
+    }
+
+    // The points drawn: points missing a coordinate are left out.
+    fn points(&self) -> Vec<(f64, f64, f64)> {
+        self.x_data
+            .iter()
+            .zip(self.y_data.iter())
+            .zip(self.z_data.iter())
+            .map(|((&x, &y), &z)| (x, y, z))
+            .filter(|(x, y, z)| x.is_finite() && y.is_finite() && z.is_finite())
+            .collect()
+    }
+
+    // The axis ranges, fitted to the points drawn.
+    fn ranges(&self, points: &[(f64, f64, f64)]) -> (Range<f64>, Range<f64>, Range<f64>) {
+        (
+            self.x_scale.fit(points.iter().map(|&(x, _, _)| x)),
+            self.y_scale.fit(points.iter().map(|&(_, y, _)| y)),
+            self.z_scale.fit(points.iter().map(|&(_, _, z)| z)),
+        )
+    }
+
+    pub fn generate_plot(&self, output_path: &str) -> Result<(), ChartError> {
+        let points = self.points();
+        if points.is_empty() {
+            return Err(ChartError::EmptyData("scatter plot has no points".to_string()));
+        }
+        let (x_range, y_range, z_range) = self.ranges(&points);
+        let root = BitMapBackend::gif(output_path, self.size, self.frame_delay)
+            .map_err(|err| ChartError::Render(err.to_string()))?
+            .into_drawing_area();
+
+        for frame in 0..self.frames {
+            root.fill(&WHITE)?;
+
+            let mut chart = ChartBuilder::on(&root)
+                .caption(&self.caption, ("sans-serif", 40))
+                .margin(20)
+                .build_cartesian_3d(x_range.clone(), y_range.clone(), z_range.clone())?;
+
+            let pitch = if self.frames > 1 {
+                1.57 - (1.57 - frame as f64 / 50.0).abs()
+            } else {
+                self.pitch
+            };
+            chart.with_projection(|mut pb| {
+                pb.pitch = pitch;
+                pb.yaw = self.yaw;
+                pb.scale = self.scale;
+                pb.into_matrix()
+            });
+
+            chart
+                .configure_axes()
+                .x_labels(10)
+                .y_labels(10)
+                .z_labels(10)
+                .draw()?;
+
+            chart.draw_series(points.iter().map(|&(x, y, z)| Circle::new((x, y, z), 3, RED.filled())))?;
+
+            root.present()?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn leaves_out_points_with_gaps() {
+        let plot = ScatterPlot::new(vec![1.0, f64::NAN, 3.0, 4.0], vec![10.0, 20.0, f64::INFINITY, 40.0])
+            .groups(vec!["a".to_string(), "b".to_string(), "c".to_string(), "d".to_string()]);
+        assert_eq!(plot.points(), vec![(1.0, 10.0, Some("a")), (4.0, 40.0, Some("d"))]);
+    }
+
+    #[test]
+    fn fits_ranges_to_plotted_points() {
+        // The x of 1000 has no y, so it does not stretch the x-axis
+        let plot = ScatterPlot::new(vec![1.0, 1000.0, 5.0], vec![2.0, f64::NAN, 8.0])
+            .x_scale(AxisScale { nice: false, padding: 0.0, ..AxisScale::default() })
+            .y_scale(AxisScale { nice: false, padding: 0.0, ..AxisScale::default() });
+        let (x_range, y_range) = plot.ranges(&plot.points());
+        assert_eq!(x_range, 1.0..5.0);
+        assert_eq!(y_range, 2.0..8.0);
+    }
+
+    #[test]
+    fn keeps_pinned_ranges() {
+        let plot = ScatterPlot::new(vec![1.0, 2.0], vec![3.0, 4.0]).x_range(0.0..10.0).y_range(-5.0..5.0);
+        assert_eq!(plot.ranges(&plot.points()), (0.0..10.0, -5.0..5.0));
+    }
+
+    #[test]
+    fn rejects_plots_without_points() {
+        let plot = ScatterPlot::new(vec![f64::NAN], vec![1.0]);
+        assert!(matches!(plot.generate_plot("unused.png"), Err(ChartError::EmptyData(_))));
+        let plot = Scatter3d::new(vec![1.0], vec![2.0], vec![f64::NAN]);
+        assert!(matches!(plot.generate_plot("unused.gif"), Err(ChartError::EmptyData(_))));
+    }
+
+    #[test]
+    fn fits_3d_ranges_to_complete_points() {
+        let plot = Scatter3d::new(vec![1.0, 2.0, -50.0], vec![3.0, 4.0, 5.0], vec![5.0, 6.0, f64::NAN])
+            .x_scale(AxisScale { nice: false, padding: 0.0, ..AxisScale::default() });
+        let points = plot.points();
+        assert_eq!(points, vec![(1.0, 3.0, 5.0), (2.0, 4.0, 6.0)]);
+        let (x_range, _, _) = plot.ranges(&points);
+        assert_eq!(x_range, 1.0..2.0);
+    }
+}
@@ -0,0 +1,265 @@
+use crate::data::{parse_number, Column, Loader};
+use crate::error::ChartError;
+use regex::Regex;
+use serde::Deserialize;
+use std::str::FromStr;
+
+/// Series left out of a chart because of their values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DropSeries {
+    /// Every selected column is drawn.
+    #[default]
+    None,
+    /// Columns without a single number are left out.
+    Missing,
+    /// Columns whose numbers are all zero are left out too.
+    Zero,
+}
+
+impl DropSeries {
+    /// Whether a column holding `cells` is left out.
+    pub fn drops(&self, cells: &[Option<String>]) -> bool {
+        let mut values = cells.iter().filter_map(|cell| cell.as_deref().and_then(parse_number));
+        match self {
+            DropSeries::None => false,
+            DropSeries::Missing => values.next().is_none(),
+            DropSeries::Zero => values.all(|value| value == 0.0),
+        }
+    }
+}
+
+impl FromStr for DropSeries {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "none" => Ok(DropSeries::None),
+            "missing" | "empty" => Ok(DropSeries::Missing),
+            "zero" => Ok(DropSeries::Zero),
+            _ => Err(format!("invalid series drop '{}', expected none, missing or zero", s)),
+        }
+    }
+}
+
+/// Which of the columns matched by `columns.y` become series of a line or
+/// grouped bar chart, e.g. for every station of a wide table but the total
+/// and the stations not yet open:
+///
+/// ```toml
+/// [columns]
+/// x = "m_month"
+/// y = ["N*", "CEN", "E1..E10", "/^S[0-9]+$/"]
+///
+/// [series]
+/// exclude = ["t_total"]
+/// drop = "zero"
+/// ```
+///
+/// Besides a header name or index, each `y` and `exclude` entry may be a
+/// glob (`N*`, `E?`), a numbered range (`E1..E10`, `N24..N14`) or a regular
+/// expression between slashes. A header matching an entry exactly is always
+/// taken as written.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SeriesFilter {
+    pub exclude: Vec<Column>,
+    pub drop: DropSeries,
+}
+
+impl SeriesFilter {
+    /// The columns matched by `patterns` in the order given, each once,
+    /// less the excluded columns and those dropped for their values.
+    pub fn select(&self, patterns: &[Column], loader: &Loader) -> Result<Vec<Column>, ChartError> {
+        let headers = loader.headers()?;
+        let mut excluded = Vec::new();
+        for pattern in &self.exclude {
+            // Exclusions need not match anything, so one list suits many files
+            excluded.extend(matches(pattern, &headers)?);
+        }
+
+        let mut selected: Vec<usize> = Vec::new();
+        for pattern in patterns {
+            let found = matches(pattern, &headers)?;
+            if found.is_empty() {
+                return Err(ChartError::ColumnNotFound { column: pattern.to_string(), available: headers });
+            }
+            for col in found {
+                if !selected.contains(&col) && !excluded.contains(&col) {
+                    selected.push(col);
+                }
+            }
+        }
+
+        let mut columns: Vec<Column> = selected.into_iter().map(Column::Index).collect();
+        if self.drop != DropSeries::None {
+            // Every candidate is read at once, not the file once per column
+            let cells = loader.columns(&columns)?;
+            let mut kept = cells.iter().map(|cells| !self.drop.drops(cells));
+            columns.retain(|_| kept.next().unwrap_or(true));
+        }
+        if columns.is_empty() && !patterns.is_empty() {
+            return Err(ChartError::EmptyData("every y column was excluded or dropped".to_string()));
+        }
+        Ok(columns)
+    }
+}
+
+/// The positions within `headers` of the columns `pattern` matches, in
+/// file order except for a range, which follows its direction.
+pub fn matches(pattern: &Column, headers: &[String]) -> Result<Vec<usize>, ChartError> {
+    let name = match pattern {
+        Column::Index(_) => return Ok(pattern.resolve(headers).into_iter().collect()),
+        Column::Name(name) => name.trim(),
+    };
+    if let Some(index) = headers.iter().position(|header| header.trim() == name) {
+        return Ok(vec![index]);
+    }
+
+    let matching = |regex: &Regex| -> Vec<usize> {
+        (0..headers.len()).filter(|&i| regex.is_match(headers[i].trim())).collect()
+    };
+    if let Some(source) = name.strip_prefix('/').and_then(|rest| rest.strip_suffix('/')) {
+        let regex = Regex::new(source).map_err(|_| ChartError::parse(name, "a regular expression"))?;
+        return Ok(matching(&regex));
+    }
+    if name.contains(['*', '?']) {
+        let source: String = name
+            .split_inclusive(['*', '?'])
+            .map(|part| match part.strip_suffix(['*', '?']) {
+                Some(literal) if part.ends_with('*') => format!("{}.*", regex::escape(literal)),
+                Some(literal) => format!("{}.", regex::escape(literal)),
+                None => regex::escape(part),
+            })
+            .collect();
+        let regex = Regex::new(&format!("^{}$", source)).map_err(|_| ChartError::parse(name, "a glob"))?;
+        return Ok(matching(&regex));
+    }
+    if let Some((start, end)) = name.split_once("..") {
+        return range(name, start, end, headers);
+    }
+
+    // A plain name that matches no header may still be an index
+    Ok(pattern.resolve(headers).into_iter().collect())
+}
+
+// Columns named `prefix` followed by a number from `start` to `end`, e.g.
+// `E1..E10`, in the order the range runs.
+fn range(name: &str, start: &str, end: &str, headers: &[String]) -> Result<Vec<usize>, ChartError> {
+    let invalid = || ChartError::parse(name, "a range such as E1..E10");
+    let split = |bound: &str| -> Option<(String, u64)> {
+        let prefix = bound.trim_end_matches(|c: char| c.is_ascii_digit());
+        Some((prefix.to_string(), bound[prefix.len()..].parse().ok()?))
+    };
+    let (prefix, first) = split(start.trim()).ok_or_else(invalid)?;
+    let (end_prefix, last) = split(end.trim()).ok_or_else(invalid)?;
+    if prefix != end_prefix {
+        return Err(invalid());
+    }
+
+    let (low, high) = (first.min(last), first.max(last));
+    let mut found: Vec<(u64, usize)> = headers
+        .iter()
+        .enumerate()
+        .filter_map(|(i, header)| {
+            let number: u64 = header.trim().strip_prefix(prefix.as_str())?.parse().ok()?;
+            (low..=high).contains(&number).then_some((number, i))
+        })
+        .collect();
+    found.sort_by_key(|&(number, _)| number);
+    if first > last {
+        found.reverse();
+    }
+    Ok(found.into_iter().map(|(_, i)| i).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn headers() -> Vec<String> {
+        ["id", "m_month", "N2", "N1", "CEN", "E1", "E2", "E10", "S1", "t_total"]
+            .iter()
+            .map(|header| header.to_string())
+            .collect()
+    }
+
+    fn names(pattern: &str) -> Vec<String> {
+        let headers = headers();
+        matches(&Column::from(pattern), &headers)
+            .unwrap()
+            .into_iter()
+            .map(|i| headers[i].clone())
+            .collect()
+    }
+
+    #[test]
+    fn matches_names_and_indices() {
+        assert_eq!(names("CEN"), ["CEN"]);
+        assert_eq!(names(" CEN "), ["CEN"]);
+        assert_eq!(names("2"), ["N2"]);
+        assert!(names("W1").is_empty());
+        assert_eq!(matches(&Column::Index(9), &headers()).unwrap(), [9]);
+    }
+
+    #[test]
+    fn matches_globs() {
+        assert_eq!(names("N*"), ["N2", "N1"]);
+        assert_eq!(names("E?"), ["E1", "E2"]);
+        assert_eq!(names("*_*"), ["m_month", "t_total"]);
+    }
+
+    #[test]
+    fn matches_ranges_in_their_direction() {
+        assert_eq!(names("E1..E10"), ["E1", "E2", "E10"]);
+        assert_eq!(names("E10..E2"), ["E10", "E2"]);
+        assert_eq!(names("N1..N2"), ["N1", "N2"]);
+        assert!(matches(&Column::from("E1..N2"), &headers()).is_err());
+        assert!(matches(&Column::from("E1..E"), &headers()).is_err());
+    }
+
+    #[test]
+    fn matches_regular_expressions() {
+        assert_eq!(names("/^[NS][0-9]+$/"), ["N2", "N1", "S1"]);
+        assert_eq!(names("/total/"), ["t_total"]);
+        assert!(matches(&Column::from("/[/"), &headers()).is_err());
+    }
+
+    #[test]
+    fn drops_series_by_their_values() {
+        let cells = |cells: &[&str]| -> Vec<Option<String>> {
+            cells.iter().map(|cell| Some(cell.to_string())).collect()
+        };
+        assert!(DropSeries::Missing.drops(&cells(&["", "n/a"])));
+        assert!(!DropSeries::Missing.drops(&cells(&["", "0"])));
+        assert!(DropSeries::Zero.drops(&cells(&["0", "", "0.0"])));
+        assert!(!DropSeries::Zero.drops(&cells(&["0", "12"])));
+        assert!(!DropSeries::None.drops(&[]));
+    }
+
+    #[test]
+    fn selects_less_exclusions_and_dropped_series() {
+        let path = std::env::temp_dir().join(format!("chartkit-series-{}.csv", std::process::id()));
+        std::fs::write(&path, "id,N1,N2,E1,t_total\n1,0,5,,5\n2,0,7,,7\n").unwrap();
+        let loader = Loader::new(path.to_str().unwrap());
+        let filter = |exclude: &[&str], drop| SeriesFilter {
+            exclude: exclude.iter().map(|&pattern| Column::from(pattern)).collect(),
+            drop,
+        };
+        let patterns = [Column::from("*"), Column::from("N2")];
+
+        let all = filter(&["id", "t_*", "W*"], DropSeries::None).select(&patterns, &loader);
+        let missing = filter(&["id", "t_*"], DropSeries::Missing).select(&patterns, &loader);
+        let zero = filter(&["id", "t_*"], DropSeries::Zero).select(&patterns, &loader);
+        let none = filter(&["*"], DropSeries::None).select(&patterns, &loader);
+        let unknown = filter(&[], DropSeries::None).select(&[Column::from("W*")], &loader);
+        std::fs::remove_file(&path).unwrap();
+
+        let indices = |cols: &[usize]| cols.iter().map(|&col| Column::Index(col)).collect::<Vec<_>>();
+        assert_eq!(all.unwrap(), indices(&[1, 2, 3]));
+        assert_eq!(missing.unwrap(), indices(&[1, 2]));
+        assert_eq!(zero.unwrap(), indices(&[2]));
+        assert!(matches!(none, Err(ChartError::EmptyData(_))));
+        assert!(matches!(unknown, Err(ChartError::ColumnNotFound { .. })));
+    }
+}
@@ -0,0 +1,941 @@
+use crate::axis::{AxisScale, TickLayout};
+use crate::bar::{BarChart, BarMode, GroupedBarChart, Orientation, ValueLabels};
+use crate::binning::BinRule;
+use crate::category::CategoryFilter;
+use crate::data::{Column, Loader, MissingPolicy};
+use crate::dialect::Dialect;
+use crate::encoding::Encoding;
+use crate::error::ChartError;
+use crate::format::NumberFormat;
+use crate::histogram::HistogramChart;
+use crate::line::{LegendPosition, LineChart, LineMode};
+use crate::scatter::{Scatter3d, ScatterPlot};
+use crate::series::SeriesFilter;
+use crate::table::{Melt, Pivot};
+use crate::time::{parse_dates, Calendar, TimeUnit};
+use chrono::NaiveDate;
+use plotters::prelude::*;
+use serde::{Deserialize, Deserializer};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// The kind of chart a spec renders.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ChartKind {
+    Bar,
+    GroupedBar,
+    Line,
+    Histogram,
+    Scatter,
+    Scatter3d,
+}
+
+/// A chart described by a TOML or JSON file instead of Rust code.
+///
+/// ```toml
+/// kind = "bar"
+/// title = "Average attendance"
+/// output = "attendance.png"
+/// size = [2400, 600]
+///
+/// [data]
+/// path = "export.csv"
+///
+/// [columns]
+/// x = "team"
+/// y = "Average Attendance"
+///
+/// [axes]
+/// x_desc = "Teams"
+/// y_range = [0.0, 80000.0]
+///
+/// [style]
+/// colors = ["#228B22", "#9ACD32"]
+/// opacity = 0.7
+/// ```
+///
+/// Relative `data.path` and `output` paths are resolved against the directory
+/// of the spec file when it is loaded with [`ChartSpec::from_file`].
+/// Unknown keys are rejected, so a misspelt setting is reported rather than
+/// silently ignored.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ChartSpec {
+    pub kind: ChartKind,
+    #[serde(default)]
+    pub title: String,
+    pub output: PathBuf,
+    pub size: Option<(u32, u32)>,
+    pub data: DataSource,
+    #[serde(default)]
+    pub columns: ColumnBindings,
+    #[serde(default)]
+    pub axes: AxesSpec,
+    /// Sorting and top-N bucketing of bar chart categories.
+    #[serde(default)]
+    pub categories: CategoryFilter,
+    /// Exclusion and dropping of empty line and grouped bar series.
+    #[serde(default)]
+    pub series: SeriesFilter,
+    #[serde(default)]
+    pub style: StyleSpec,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DataSource {
+    pub path: PathBuf,
+    /// What to do with empty or non-numeric values: `"skip"` the record,
+    /// read them as `"zero"`, leave a `"gap"`, or `"fail"`.
+    #[serde(default)]
+    pub missing: MissingPolicy,
+    /// Character encoding of the file: `"auto"` (the default) detects a
+    /// byte order mark, UTF-16, UTF-8 or Thai `"windows-874"` / `"tis-620"`.
+    #[serde(default)]
+    pub encoding: Encoding,
+    /// Whether to stream the file rather than hold it in memory; by default
+    /// only files over 64 MiB are streamed.
+    #[serde(default)]
+    pub stream: Option<bool>,
+    /// Turns one column per variable into one record per variable, e.g. a
+    /// column per year into `year` and `value` columns.
+    pub melt: Option<Melt>,
+    /// Turns one record per (index, column) pair into one column per
+    /// column value, applied after `melt`.
+    pub pivot: Option<Pivot>,
+    /// Delimiter, quoting, comment lines, header row and rows to skip.
+    #[serde(flatten)]
+    pub dialect: Dialect,
+}
+
+/// Which CSV columns feed the chart, by header name or zero-based index.
+/// `y` accepts a single column or a list; line charts draw one series and
+/// grouped bar charts one group per listed column, which may also be given
+/// by pattern (see [`SeriesFilter`]).
+///
+/// A grouped bar chart with a `group` column reads long-format data instead:
+/// one row per `x` category and `group` value, with the value in `y`.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ColumnBindings {
+    pub x: Option<Column>,
+    #[serde(default, deserialize_with = "one_or_many")]
+    pub y: Vec<Column>,
+    pub z: Option<Column>,
+    pub group: Option<Column>,
+}
+
+/// How the x column of a bar or line chart is placed on the axis.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AxisType {
+    /// Evenly spaced categories in file order.
+    #[default]
+    Category,
+    /// Dates on a continuous time axis.
+    Time,
+}
+
+/// Axis titles and ranges. Axes without a `*_range` are fitted to the data;
+/// a `*_scale` table replaces the chart's default fitting entirely, e.g.
+///
+/// ```toml
+/// [axes.y_scale]
+/// padding = 0.1
+/// include_zero = true
+/// ```
+///
+/// With `x_type = "time"` the x column is parsed as dates, using
+/// `date_formats` (chrono format strings) or the defaults in
+/// [`crate::time::DEFAULT_DATE_FORMATS`], and ticked every `time_unit`.
+/// Buddhist-era years are read as such; `calendar` picks `"gregorian"`,
+/// `"buddhist"` or `"both"` for the tick labels.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AxesSpec {
+    #[serde(default)]
+    pub x_type: AxisType,
+    #[serde(default)]
+    pub date_formats: Vec<String>,
+    pub time_unit: Option<TimeUnit>,
+    pub calendar: Option<Calendar>,
+    /// Axis titles. `{unit}` stands for the unit stripped from the axis
+    /// column's values (`£`, `%`, ...); without a title, an axis whose
+    /// values carry a unit is titled with the column name and unit.
+    pub x_desc: Option<String>,
+    pub y_desc: Option<String>,
+    pub x_range: Option<(f64, f64)>,
+    pub y_range: Option<(f64, f64)>,
+    pub z_range: Option<(f64, f64)>,
+    pub x_scale: Option<AxisScale>,
+    pub y_scale: Option<AxisScale>,
+    pub z_scale: Option<AxisScale>,
+    pub x_labels: Option<usize>,
+    /// Category label layout: `"auto"`, `"horizontal"`, `"wrap"`,
+    /// `"rotate45"` or `"rotate90"`.
+    pub x_tick_layout: Option<TickLayout>,
+    pub y_labels: Option<usize>,
+    /// Numeric tick labels: `"plain"`, `"fixed:2"`, `"thousands"`, `"si"`,
+    /// `"percent"`, `"currency"`, or a table such as
+    /// `{ style = "thousands", locale = "de" }`.
+    pub x_format: Option<NumberFormat>,
+    pub y_format: Option<NumberFormat>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct StyleSpec {
+    /// Colors as `#RRGGBB` strings, cycled through bars or used as the
+    /// histogram fill.
+    #[serde(default)]
+    pub colors: Vec<String>,
+    /// Colors for scatter plot groups, keyed by group value.
+    #[serde(default)]
+    pub group_colors: BTreeMap<String, String>,
+    /// Opacity of the colors, or of the area fills of a line chart.
+    pub opacity: Option<f64>,
+    pub bar_width: Option<f64>,
+    /// Histogram binning: a bin count, `"sturges"`, `"scott"`, `"fd"` or
+    /// `"width:<w>"`.
+    pub bins: Option<BinRule>,
+    pub frames: Option<u32>,
+    /// Bar chart direction: `"vertical"` or `"horizontal"`.
+    pub orientation: Option<Orientation>,
+    /// Grouped bar layout: `"grouped"`, `"stacked"` or `"percent-stacked"`.
+    pub bar_mode: Option<BarMode>,
+    /// Bar value labels: `true` for the defaults, or a table such as
+    /// `{ position = "inside", format = "si", suffix = " fans" }`.
+    #[serde(default, deserialize_with = "flag_or_labels")]
+    pub value_labels: Option<ValueLabels>,
+    /// Line chart legend: `"auto"`, a corner such as `"upper-left"`,
+    /// `"outside"` the plot area or `"none"`.
+    pub legend: Option<LegendPosition>,
+    /// Line chart layout: `"line"`, `"area"`, `"stacked"`,
+    /// `"percent-stacked"` or `"stream"`.
+    pub line_mode: Option<LineMode>,
+    /// The y value line chart areas are filled down to and stacks start from.
+    pub baseline: Option<f64>,
+}
+
+fn one_or_many<'de, D>(deserializer: D) -> Result<Vec<Column>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum OneOrMany {
+        One(Column),
+        Many(Vec<Column>),
+    }
+
+    Ok(match OneOrMany::deserialize(deserializer)? {
+        OneOrMany::One(col) => vec![col],
+        OneOrMany::Many(cols) => cols,
+    })
+}
+
+fn flag_or_labels<'de, D>(deserializer: D) -> Result<Option<ValueLabels>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum FlagOrLabels {
+        Flag(bool),
+        Labels(ValueLabels),
+    }
+
+    Ok(match FlagOrLabels::deserialize(deserializer)? {
+        FlagOrLabels::Flag(true) => Some(ValueLabels::default()),
+        FlagOrLabels::Flag(false) => None,
+        FlagOrLabels::Labels(labels) => Some(labels),
+    })
+}
+
+/// Parses a `#RRGGBB` (or `RRGGBB`) hex color.
+pub fn parse_color(hex: &str) -> Result<RGBColor, ChartError> {
+    let digits = hex.trim().trim_start_matches('#');
+    if digits.len() != 6 || !digits.is_ascii() {
+        return Err(ChartError::parse(hex, "a #RRGGBB color"));
+    }
+    let channel = |i: usize| {
+        u8::from_str_radix(&digits[i..i + 2], 16).map_err(|_| ChartError::parse(hex, "a #RRGGBB color"))
+    };
+    Ok(RGBColor(channel(0)?, channel(2)?, channel(4)?))
+}
+
+// A fixed range wins over a scale table; `None` keeps the chart's default.
+// `name` is the range setting, reported when the range is empty.
+fn to_scale(
+    name: &str,
+    range: Option<(f64, f64)>,
+    scale: &Option<AxisScale>,
+) -> Result<Option<AxisScale>, ChartError> {
+    match range {
+        Some((start, end)) if start >= end => Err(ChartError::Range(format!(
+            "{} [{}, {}] is empty; the start must be below the end",
+            name, start, end
+        ))),
+        Some((start, end)) => Ok(Some(AxisScale::fixed(start..end))),
+        None => Ok(scale.clone()),
+    }
+}
+
+impl ChartSpec {
+    /// Loads a spec from a `.toml` or `.json` file.
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, ChartError> {
+        let path = path.as_ref();
+        let name = path.display().to_string();
+        let text = fs::read_to_string(path).map_err(|err| ChartError::io(&name, err))?;
+        let spec_error = |message: String| ChartError::Spec { path: name.clone(), message };
+        let mut spec: ChartSpec = match path.extension().and_then(|ext| ext.to_str()) {
+            Some("json") => serde_json::from_str(&text).map_err(|err| spec_error(err.to_string()))?,
+            Some("toml") => toml::from_str(&text).map_err(|err| spec_error(err.to_string()))?,
+            _ => return Err(spec_error("unsupported spec format, expected .toml or .json".to_string())),
+        };
+
+        if let Some(base) = path.parent() {
+            spec.data.path = base.join(&spec.data.path);
+            spec.output = base.join(&spec.output);
+        }
+
+        Ok(spec)
+    }
+
+    pub fn from_toml_str(text: &str) -> Result<Self, ChartError> {
+        toml::from_str(text).map_err(|err| ChartError::Spec {
+            path: "<toml>".to_string(),
+            message: err.to_string(),
+        })
+    }
+
+    pub fn from_json_str(text: &str) -> Result<Self, ChartError> {
+        serde_json::from_str(text).map_err(|err| ChartError::Spec {
+            path: "<json>".to_string(),
+            message: err.to_string(),
+        })
+    }
+
+    fn x_column(&self) -> Result<Column, ChartError> {
+        self.columns
+            .x
+            .clone()
+            .ok_or_else(|| ChartError::Schema(format!("{:?} chart requires columns.x", self.kind)))
+    }
+
+    fn y_column(&self) -> Result<Column, ChartError> {
+        self.columns
+            .y
+            .first()
+            .cloned()
+            .ok_or_else(|| ChartError::Schema(format!("{:?} chart requires columns.y", self.kind)))
+    }
+
+    fn colors(&self) -> Result<Vec<RGBAColor>, ChartError> {
+        let opacity = self.style.opacity.unwrap_or(1.0);
+        self.style
+            .colors
+            .iter()
+            .map(|hex| Ok(parse_color(hex)?.mix(opacity)))
+            .collect()
+    }
+
+    // The x labels parsed as dates when the x-axis is a time axis.
+    fn x_dates(&self, labels: &[String]) -> Result<Option<Vec<NaiveDate>>, ChartError> {
+        match self.axes.x_type {
+            AxisType::Category => Ok(None),
+            AxisType::Time => Ok(Some(parse_dates(labels, &self.axes.date_formats)?)),
+        }
+    }
+
+    // Rejects settings that conflict with each other before any data is
+    // read.
+    fn validate(&self) -> Result<(), ChartError> {
+        let categories = &self.categories;
+        if self.axes.x_type == AxisType::Time && categories.top.is_some() {
+            if let Some(other) = &categories.other {
+                return Err(ChartError::Schema(format!(
+                    "cannot sum the categories cut by top into '{}' on a time x-axis, which needs a \
+                     date for every bar; drop categories.other (--other) or categories.top (--top)",
+                    other
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    // The series columns: the `y` patterns expanded and filtered, never
+    // empty.
+    fn y_columns(&self, loader: &Loader) -> Result<Vec<Column>, ChartError> {
+        // Requires at least one pattern, each matching a column
+        self.y_column()?;
+        self.series.select(&self.columns.y, loader)
+    }
+
+    // Header names of `cols`, used to name series and groups.
+    fn y_names(&self, loader: &Loader, cols: &[Column]) -> Result<Vec<String>, ChartError> {
+        let headers = loader.headers()?;
+        cols.iter().map(|col| Ok(headers[col.resolve(&headers)?].clone())).collect()
+    }
+
+    // The title of an axis showing `col`: `desc` with `{unit}` replaced by
+    // the unit stripped from the column's values, or without a `desc` the
+    // column name followed by its unit, e.g. "Cost (£)".
+    fn unit_desc(
+        &self,
+        loader: &Loader,
+        desc: &Option<String>,
+        col: &Column,
+    ) -> Result<Option<String>, ChartError> {
+        let unit = loader.unit(col.clone())?;
+        Ok(match (desc, unit) {
+            (Some(desc), Some(unit)) => Some(desc.replace("{unit}", &unit)),
+            (Some(desc), None) => Some(desc.replace(" ({unit})", "").replace("{unit}", "")),
+            (None, Some(unit)) => {
+                let headers = loader.headers()?;
+                Some(format!("{} ({})", headers[col.resolve(&headers)?], unit))
+            }
+            (None, None) => None,
+        })
+    }
+
+    /// Reads the data source and writes the chart to `output`.
+    pub fn render(&self) -> Result<(), ChartError> {
+        self.validate()?;
+        let data_path = self.data.path.to_string_lossy();
+        let output = self.output.to_string_lossy();
+        let axes = &self.axes;
+        let mut loader = Loader::new(&data_path)
+            .missing(self.data.missing)
+            .encoding(self.data.encoding)
+            .dialect(self.data.dialect.clone());
+        if let Some(stream) = self.data.stream {
+            loader = loader.stream(stream);
+        }
+        if let Some(melt) = &self.data.melt {
+            loader = loader.melt(melt.clone());
+        }
+        if let Some(pivot) = &self.data.pivot {
+            loader = loader.pivot(pivot.clone());
+        }
+
+        let result = match self.kind {
+            ChartKind::Bar => {
+                let (x_data, mut columns) =
+                    loader.labeled(self.x_column()?, &[self.y_column()?])?;
+                let (x_data, y_data) = self.categories.apply(x_data, columns.remove(0));
+                let dates = self.x_dates(&x_data)?;
+                let mut chart = BarChart::new(x_data, y_data)
+                    .caption(&self.title)
+                    .colors(self.colors()?);
+                if let Some(dates) = dates {
+                    chart = chart.dates(dates);
+                }
+                if let Some(unit) = axes.time_unit {
+                    chart = chart.time_unit(unit);
+                }
+                if let Some(calendar) = axes.calendar {
+                    chart = chart.calendar(calendar);
+                }
+                if let Some((width, height)) = self.size {
+                    chart = chart.size(width, height);
+                }
+                if let Some(desc) = &axes.x_desc {
+                    chart = chart.x_desc(desc);
+                }
+                if let Some(layout) = axes.x_tick_layout {
+                    chart = chart.x_tick_layout(layout);
+                }
+                if let Some(desc) = self.unit_desc(&loader, &axes.y_desc, &self.y_column()?)? {
+                    chart = chart.y_desc(&desc);
+                }
+                if let Some(scale) = to_scale("y_range", axes.y_range, &axes.y_scale)? {
+                    chart = chart.y_scale(scale);
+                }
+                if let Some(count) = axes.x_labels {
+                    chart = chart.x_labels(count);
+                }
+                if let Some(count) = axes.y_labels {
+                    chart = chart.y_labels(count);
+                }
+                if let Some(format) = &axes.y_format {
+                    chart = chart.y_format(format.clone());
+                }
+                if let Some(width) = self.style.bar_width {
+                    chart = chart.bar_width(width);
+                }
+                if let Some(orientation) = self.style.orientation {
+                    chart = chart.orientation(orientation);
+                }
+                if let Some(labels) = &self.style.value_labels {
+                    chart = chart.value_labels(labels.clone());
+                }
+                chart.generate_chart(&output)
+            }
+            ChartKind::GroupedBar => {
+                let (categories, groups, value_col) = match &self.columns.group {
+                    Some(group) => {
+                        let value_col = self.y_column()?;
+                        let (categories, groups) =
+                            loader.grouped(self.x_column()?, group.clone(), value_col.clone())?;
+                        (categories, groups, value_col)
+                    }
+                    None => {
+                        let y = self.y_columns(&loader)?;
+                        let (categories, columns) = loader.labeled(self.x_column()?, &y)?;
+                        let groups = self.y_names(&loader, &y)?.into_iter().zip(columns).collect();
+                        (categories, groups, y[0].clone())
+                    }
+                };
+                let mut chart = GroupedBarChart::new(categories)
+                    .groups(groups)
+                    .caption(&self.title)
+                    .colors(self.colors()?);
+                if let Some((width, height)) = self.size {
+                    chart = chart.size(width, height);
+                }
+                if let Some(desc) = &axes.x_desc {
+                    chart = chart.x_desc(desc);
+                }
+                if let Some(layout) = axes.x_tick_layout {
+                    chart = chart.x_tick_layout(layout);
+                }
+                if let Some(desc) = self.unit_desc(&loader, &axes.y_desc, &value_col)? {
+                    chart = chart.y_desc(&desc);
+                }
+                if let Some(scale) = to_scale("y_range", axes.y_range, &axes.y_scale)? {
+                    chart = chart.y_scale(scale);
+                }
+                if let Some(count) = axes.y_labels {
+                    chart = chart.y_labels(count);
+                }
+                if let Some(format) = &axes.y_format {
+                    chart = chart.y_format(format.clone());
+                }
+                if let Some(width) = self.style.bar_width {
+                    chart = chart.group_width(width);
+                }
+                if let Some(mode) = self.style.bar_mode {
+                    chart = chart.mode(mode);
+                }
+                if let Some(labels) = &self.style.value_labels {
+                    chart = chart.value_labels(labels.clone());
+                }
+                chart.generate_chart(&output)
+            }
+            ChartKind::Line => {
+                let y = self.y_columns(&loader)?;
+                let (x_data, columns) = loader.labeled(self.x_column()?, &y)?;
+                let dates = self.x_dates(&x_data)?;
+                let mut chart = LineChart::new(x_data).caption(&self.title);
+                if let Some(dates) = dates {
+                    chart = chart.dates(dates);
+                }
+                if let Some(unit) = axes.time_unit {
+                    chart = chart.time_unit(unit);
+                }
+                if let Some(calendar) = axes.calendar {
+                    chart = chart.calendar(calendar);
+                }
+                for (name, values) in self.y_names(&loader, &y)?.iter().zip(columns) {
+                    chart = chart.series(name, values);
+                }
+                if let Some((width, height)) = self.size {
+                    chart = chart.size(width, height);
+                }
+                if let Some(desc) = &axes.x_desc {
+                    chart = chart.x_desc(desc);
+                }
+                if let Some(layout) = axes.x_tick_layout {
+                    chart = chart.x_tick_layout(layout);
+                }
+                if let Some(desc) = self.unit_desc(&loader, &axes.y_desc, &y[0])? {
+                    chart = chart.y_desc(&desc);
+                }
+                if let Some(scale) = to_scale("y_range", axes.y_range, &axes.y_scale)? {
+                    chart = chart.y_scale(scale);
+                }
+                if let Some(count) = axes.y_labels {
+                    chart = chart.y_labels(count);
+                }
+                if let Some(format) = &axes.y_format {
+                    chart = chart.y_format(format.clone());
+                }
+                if let Some(legend) = self.style.legend {
+                    chart = chart.legend(legend);
+                }
+                if let Some(mode) = self.style.line_mode {
+                    chart = chart.mode(mode);
+                }
+                if let Some(opacity) = self.style.opacity {
+                    chart = chart.fill_opacity(opacity);
+                }
+                if let Some(baseline) = self.style.baseline {
+                    chart = chart.baseline(baseline);
+                }
+                chart.generate_chart(&output)
+            }
+            ChartKind::Histogram => {
+                let mut columns = loader.numeric(&[self.x_column()?])?;
+                let mut chart = HistogramChart::new(columns.remove(0)).caption(&self.title);
+                if let Some((width, height)) = self.size {
+                    chart = chart.size(width, height);
+                }
+                if let Some(desc) = self.unit_desc(&loader, &axes.x_desc, &self.x_column()?)? {
+                    chart = chart.x_desc(&desc);
+                }
+                if let Some(desc) = &axes.y_desc {
+                    chart = chart.y_desc(desc);
+                }
+                if let Some(rule) = self.style.bins {
+                    chart = chart.bins(rule);
+                }
+                if let Some(format) = &axes.x_format {
+                    chart = chart.x_format(format.clone());
+                }
+                if let Some(format) = &axes.y_format {
+                    chart = chart.y_format(format.clone());
+                }
+                if let Some(scale) = to_scale("y_range", axes.y_range, &axes.y_scale)? {
+                    chart = chart.y_scale(scale);
+                }
+                if let Some(color) = self.colors()?.first() {
+                    chart = chart.color(*color);
+                }
+                chart.generate_chart(&output)
+            }
+            ChartKind::Scatter => {
+                let cols = [self.x_column()?, self.y_column()?];
+                let mut chart = match &self.columns.group {
+                    Some(group) => {
+                        let (groups, mut columns) = loader.labeled(group.clone(), &cols)?;
+                        let y_data = columns.remove(1);
+                        let mut chart = ScatterPlot::new(columns.remove(0), y_data).groups(groups);
+                        for (name, hex) in &self.style.group_colors {
+                            chart = chart.group_color(name, parse_color(hex)?);
+                        }
+                        chart
+                    }
+                    None => {
+                        let mut columns = loader.numeric(&cols)?;
+                        let y_data = columns.remove(1);
+                        ScatterPlot::new(columns.remove(0), y_data)
+                    }
+                };
+                chart = chart.caption(&self.title);
+                if let Some(color) = self.style.colors.first() {
+                    chart = chart.default_color(parse_color(color)?);
+                }
+                if let Some((width, height)) = self.size {
+                    chart = chart.size(width, height);
+                }
+                if let Some(desc) = self.unit_desc(&loader, &axes.x_desc, &self.x_column()?)? {
+                    chart = chart.x_desc(&desc);
+                }
+                if let Some(desc) = self.unit_desc(&loader, &axes.y_desc, &self.y_column()?)? {
+                    chart = chart.y_desc(&desc);
+                }
+                if let Some(scale) = to_scale("x_range", axes.x_range, &axes.x_scale)? {
+                    chart = chart.x_scale(scale);
+                }
+                if let Some(scale) = to_scale("y_range", axes.y_range, &axes.y_scale)? {
+                    chart = chart.y_scale(scale);
+                }
+                if let Some(count) = axes.x_labels {
+                    chart = chart.x_labels(count);
+                }
+                if let Some(count) = axes.y_labels {
+                    chart = chart.y_labels(count);
+                }
+                if let Some(format) = &axes.x_format {
+                    chart = chart.x_format(format.clone());
+                }
+                if let Some(format) = &axes.y_format {
+                    chart = chart.y_format(format.clone());
+                }
+                chart.generate_plot(&output)
+            }
+            ChartKind::Scatter3d => {
+                let z_col = self
+                    .columns
+                    .z
+                    .clone()
+                    .ok_or_else(|| ChartError::Schema("scatter3d chart requires columns.z".to_string()))?;
+                let mut columns =
+                    loader.numeric(&[self.x_column()?, self.y_column()?, z_col])?;
+                let z_data = columns.remove(2);
+                let y_data = columns.remove(1);
+                let mut chart = Scatter3d::new(columns.remove(0), y_data, z_data).caption(&self.title);
+                if let Some((width, height)) = self.size {
+                    chart = chart.size(width, height);
+                }
+                if let Some(scale) = to_scale("x_range", axes.x_range, &axes.x_scale)? {
+                    chart = chart.x_scale(scale);
+                }
+                if let Some(scale) = to_scale("y_range", axes.y_range, &axes.y_scale)? {
+                    chart = chart.y_scale(scale);
+                }
+                if let Some(scale) = to_scale("z_range", axes.z_range, &axes.z_scale)? {
+                    chart = chart.z_scale(scale);
+                }
+                if let Some(frames) = self.style.frames {
+                    chart = chart.frames(frames);
+                }
+                chart.generate_plot(&output)
+            }
+        };
+        loader.diagnostics().report();
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bar_spec(extra: &str) -> ChartSpec {
+        let text = format!(
+            "kind = \"bar\"\noutput = \"out.png\"\n\n[data]\npath = \"missing.csv\"\n\n\
+             [columns]\nx = \"month\"\ny = \"sales\"\n\n{}",
+            extra
+        );
+        ChartSpec::from_toml_str(&text).unwrap()
+    }
+
+    fn spec_error(result: Result<ChartSpec, ChartError>) -> String {
+        match result {
+            Err(ChartError::Spec { message, .. }) => message,
+            other => panic!("expected a spec error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn parses_toml_spec() {
+        let spec = ChartSpec::from_toml_str(
+            r##"
+            kind = "line"
+            title = "Rainfall"
+            output = "rain.png"
+            size = [1200, 600]
+
+            [data]
+            path = "rain.csv"
+            missing = "gap"
+            delimiter = ";"
+            header_row = 2
+
+            [columns]
+            x = "month"
+            y = ["north", 2]
+
+            [axes]
+            x_type = "time"
+            y_range = [0.0, 300.0]
+
+            [style]
+            colors = ["#1f77b4"]
+            line_mode = "stacked"
+            legend = "upper-left"
+            value_labels = { suffix = " mm" }
+            "##,
+        )
+        .unwrap();
+
+        assert_eq!(spec.kind, ChartKind::Line);
+        assert_eq!(spec.title, "Rainfall");
+        assert_eq!(spec.size, Some((1200, 600)));
+        assert_eq!(spec.data.missing, MissingPolicy::Gap);
+        assert_eq!(spec.data.dialect.delimiter, Some(';'));
+        assert_eq!(spec.data.dialect.header_row, 2);
+        assert_eq!(spec.columns.x, Some(Column::Name("month".to_string())));
+        assert_eq!(spec.columns.y, vec![Column::Name("north".to_string()), Column::Index(2)]);
+        assert_eq!(spec.axes.x_type, AxisType::Time);
+        assert_eq!(spec.axes.y_range, Some((0.0, 300.0)));
+        assert_eq!(spec.style.line_mode, Some(LineMode::Stacked));
+        assert_eq!(spec.style.legend, Some(LegendPosition::UpperLeft));
+        assert_eq!(spec.style.value_labels.unwrap().suffix, " mm");
+    }
+
+    #[test]
+    fn parses_json_spec() {
+        let spec = ChartSpec::from_json_str(
+            r#"{
+                "kind": "grouped-bar",
+                "output": "medals.png",
+                "data": { "path": "medals.csv" },
+                "columns": { "x": "country", "y": "gold" },
+                "style": { "bar_mode": "percent-stacked", "value_labels": true }
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(spec.kind, ChartKind::GroupedBar);
+        assert_eq!(spec.columns.y, vec![Column::Name("gold".to_string())]);
+        assert_eq!(spec.style.bar_mode, Some(BarMode::PercentStacked));
+        assert_eq!(spec.style.value_labels, Some(ValueLabels::default()));
+    }
+
+    #[test]
+    fn fills_in_defaults() {
+        let spec = ChartSpec::from_toml_str("kind = \"histogram\"\noutput = \"h.png\"\n[data]\npath = \"h.csv\"\n")
+            .unwrap();
+
+        assert_eq!(spec.title, "");
+        assert_eq!(spec.size, None);
+        assert_eq!(spec.data.missing, MissingPolicy::Skip);
+        assert_eq!(spec.data.encoding, Encoding::default());
+        assert_eq!(spec.data.dialect, Dialect::default());
+        assert_eq!(spec.data.stream, None);
+        assert!(spec.columns.x.is_none() && spec.columns.y.is_empty());
+        assert_eq!(spec.axes.x_type, AxisType::Category);
+        assert_eq!(spec.categories, CategoryFilter::default());
+        assert!(spec.style.colors.is_empty());
+        assert_eq!(spec.style.value_labels, None);
+    }
+
+    #[test]
+    fn rejects_unknown_fields() {
+        let base = "kind = \"bar\"\noutput = \"out.png\"\n";
+        for (extra, field) in [
+            ("colour = \"red\"\n[data]\npath = \"a.csv\"\n", "colour"),
+            ("[data]\npath = \"a.csv\"\ndelimeter = \";\"\n", "delimeter"),
+            ("[data]\npath = \"a.csv\"\n[columns]\nx = \"a\"\nyy = \"b\"\n", "yy"),
+            ("[data]\npath = \"a.csv\"\n[axes]\ny_rnage = [0.0, 1.0]\n", "y_rnage"),
+            ("[data]\npath = \"a.csv\"\n[axes.y_scale]\nnicer = true\n", "nicer"),
+            ("[data]\npath = \"a.csv\"\n[style]\nopactiy = 0.5\n", "opactiy"),
+            ("[data]\npath = \"a.csv\"\n[categories]\nlimit = 3\n", "limit"),
+        ] {
+            let message = spec_error(ChartSpec::from_toml_str(&format!("{}{}", base, extra)));
+            assert!(message.contains(field), "{}: {}", field, message);
+        }
+    }
+
+    #[test]
+    fn rejects_unknown_modes() {
+        let base = "output = \"out.png\"\n";
+        for extra in [
+            "kind = \"pie\"\n[data]\npath = \"a.csv\"\n",
+            "kind = \"line\"\n[data]\npath = \"a.csv\"\n[style]\nline_mode = \"ribbon\"\n",
+            "kind = \"bar\"\n[data]\npath = \"a.csv\"\n[style]\nbar_mode = \"overlap\"\n",
+            "kind = \"bar\"\n[data]\npath = \"a.csv\"\nmissing = \"drop\"\n",
+            "kind = \"bar\"\n[data]\npath = \"a.csv\"\n[axes]\nx_type = \"log\"\n",
+        ] {
+            spec_error(ChartSpec::from_toml_str(&format!("{}{}", base, extra)));
+        }
+        assert!(ChartSpec::from_json_str(r#"{"kind": "bar", "output": "o.png"}"#).is_err());
+    }
+
+    #[test]
+    fn resolves_paths_against_the_spec_file() {
+        let dir = std::env::temp_dir().join(format!("chartkit-spec-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let toml_path = dir.join("chart.toml");
+        let other_path = dir.join("chart.yaml");
+        fs::write(&toml_path, "kind = \"bar\"\noutput = \"out/chart.png\"\n[data]\npath = \"data.csv\"\n").unwrap();
+        fs::write(&other_path, "kind: bar\n").unwrap();
+        let spec = ChartSpec::from_file(&toml_path);
+        let unsupported = ChartSpec::from_file(&other_path);
+        fs::remove_dir_all(&dir).unwrap();
+
+        let spec = spec.unwrap();
+        assert_eq!(spec.data.path, dir.join("data.csv"));
+        assert_eq!(spec.output, dir.join("out/chart.png"));
+        assert!(spec_error(unsupported).contains("unsupported spec format"));
+    }
+
+    #[test]
+    fn parses_bundled_specs() {
+        let root = Path::new(env!("CARGO_MANIFEST_DIR")).parent().unwrap();
+        let mut count = 0;
+        for chart in ["bar_chart", "histogram", "line_chart", "scatter_plot"] {
+            for entry in fs::read_dir(root.join(chart).join("charts")).unwrap() {
+                let path = entry.unwrap().path();
+                let spec = ChartSpec::from_file(&path);
+                assert!(spec.is_ok(), "{}: {:?}", path.display(), spec.err());
+                count += 1;
+            }
+        }
+        assert!(count > 0);
+    }
+
+    #[test]
+    fn rejects_other_bucket_on_time_axis() {
+        let spec = bar_spec("[axes]\nx_type = \"time\"\n\n[categories]\nsort = \"value\"\ntop = 3\nother = \"Other\"\n");
+        match spec.render() {
+            Err(ChartError::Schema(message)) => assert!(message.contains("'Other'"), "{}", message),
+            other => panic!("expected a schema error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn allows_top_without_other_on_time_axis() {
+        let spec = bar_spec("[axes]\nx_type = \"time\"\n\n[categories]\ntop = 3\n");
+        assert!(spec.validate().is_ok());
+        let spec = bar_spec("[categories]\ntop = 3\nother = \"Other\"\n");
+        assert!(spec.validate().is_ok());
+    }
+
+    #[test]
+    fn renders_a_spec_file() {
+        let dir = std::env::temp_dir().join(format!("chartkit-spec-render-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("teams.csv"), "team, Average Attendance\nLeeds,36000\nYork,8000\n").unwrap();
+        let spec_path = dir.join("attendance.json");
+        fs::write(
+            &spec_path,
+            r##"{
+                "kind": "bar",
+                "title": "Average attendance",
+                "output": "attendance.png",
+                "size": [400, 300],
+                "data": { "path": "teams.csv" },
+                "columns": { "x": "team", "y": "Average Attendance" },
+                "style": { "colors": ["#228B22"] }
+            }"##,
+        )
+        .unwrap();
+        let result = ChartSpec::from_file(&spec_path).and_then(|spec| spec.render());
+        let written = dir.join("attendance.png").exists();
+        fs::remove_dir_all(&dir).unwrap();
+
+        result.unwrap();
+        assert!(written);
+    }
+
+    #[test]
+    fn reports_the_spec_file_that_fails() {
+        let path = std::env::temp_dir().join(format!("chartkit-spec-invalid-{}.toml", std::process::id()));
+        fs::write(&path, "kind = \"bar\"\noutput = \n").unwrap();
+        let invalid = ChartSpec::from_file(&path);
+        fs::remove_file(&path).unwrap();
+
+        match invalid {
+            Err(ChartError::Spec { path: name, .. }) => assert_eq!(name, path.display().to_string()),
+            other => panic!("expected a spec error, got {:?}", other),
+        }
+        assert!(matches!(ChartSpec::from_file(&path), Err(ChartError::Io { .. })));
+    }
+
+    #[test]
+    fn parses_hex_colors() {
+        assert_eq!(parse_color("#228B22").unwrap(), RGBColor(0x22, 0x8b, 0x22));
+        assert_eq!(parse_color(" 9acd32 ").unwrap(), RGBColor(0x9a, 0xcd, 0x32));
+        for invalid in ["#12345", "#1234567", "#GG0000", "#ééé"] {
+            assert!(matches!(parse_color(invalid), Err(ChartError::Parse { .. })), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn prefers_fixed_ranges_over_scales() {
+        let scale = Some(AxisScale::from_zero());
+        assert_eq!(to_scale("y_range", Some((1.0, 5.0)), &scale).unwrap(), Some(AxisScale::fixed(1.0..5.0)));
+        assert_eq!(to_scale("y_range", None, &scale).unwrap(), scale);
+        assert_eq!(to_scale("y_range", None, &None).unwrap(), None);
+        match to_scale("y_range", Some((5.0, 5.0)), &scale) {
+            Err(ChartError::Range(message)) => assert!(message.starts_with("y_range [5, 5]"), "{}", message),
+            other => panic!("expected a range error, got {:?}", other),
+        }
+    }
+}
@@ -0,0 +1,406 @@
+use crate::data::{parse_value, Column};
+use crate::dialect::Dialect;
+use crate::encoding::{read_text, Encoding};
+use crate::error::ChartError;
+use crate::series::matches;
+use serde::Deserialize;
+use std::collections::{HashMap, VecDeque};
+use std::io::Read;
+
+/// A CSV file held in memory column by column, read in a single pass, so
+/// any number of series can be drawn from it without reading the file again.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Table {
+    headers: Vec<String>,
+    lines: Vec<u64>,
+    columns: Vec<Vec<Option<String>>>,
+}
+
+impl Table {
+    /// Reads every column of a CSV file.
+    pub fn read(path: &str, encoding: Encoding, dialect: &Dialect) -> Result<Self, ChartError> {
+        let text = read_text(path, encoding)?;
+        let body = dialect.body(&text);
+        let mut rdr = dialect.reader(body, body.as_bytes());
+        let headers: Vec<String> = rdr
+            .headers()
+            .map_err(|err| ChartError::csv(path, err))?
+            .iter()
+            .map(|h| h.to_string())
+            .collect();
+
+        let mut table = Table::with_headers(headers);
+        for_each_record(path, dialect, &mut rdr, |line, record| {
+            table.lines.push(line);
+            for (col, column) in table.columns.iter_mut().enumerate() {
+                column.push(record.get(col).map(|s| s.to_string()));
+            }
+            Ok(())
+        })?;
+        Ok(table)
+    }
+
+    /// The header row as written, surrounding whitespace included.
+    pub fn headers(&self) -> &[String] {
+        &self.headers
+    }
+
+    /// The number of records.
+    pub fn len(&self) -> usize {
+        self.lines.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lines.is_empty()
+    }
+
+    /// The line each record starts on; the header is line 1.
+    pub fn lines(&self) -> &[u64] {
+        &self.lines
+    }
+
+    /// The cells of a column, `None` where a record is too short to hold it.
+    pub fn column(&self, col: &Column) -> Result<&[Option<String>], ChartError> {
+        Ok(&self.columns[col.resolve(&self.headers)?])
+    }
+
+    // An empty table with these headers.
+    fn with_headers(headers: Vec<String>) -> Self {
+        Table {
+            columns: vec![Vec::new(); headers.len()],
+            headers,
+            lines: Vec::new(),
+        }
+    }
+}
+
+/// Turns a wide table long: every value column of a record becomes a record
+/// of its own holding the `id` cells, the column name under `variable` and
+/// the cell under `value`. For one column per year,
+///
+/// ```toml
+/// [data.melt]
+/// id = ["month"]
+/// variable = "year"
+/// value = "passengers"
+/// ```
+///
+/// turns `month,2561,2562` into `month,year,passengers` with two records per
+/// month. The value columns are those listed in `columns`, by name, index or
+/// pattern (see [`crate::SeriesFilter`]), or all but the `id` columns; an
+/// `id` column is never melted, even when a pattern matches it.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Melt {
+    pub id: Vec<Column>,
+    pub columns: Vec<Column>,
+    pub variable: String,
+    pub value: String,
+}
+
+impl Default for Melt {
+    fn default() -> Self {
+        Melt {
+            id: Vec::new(),
+            columns: Vec::new(),
+            variable: "variable".to_string(),
+            value: "value".to_string(),
+        }
+    }
+}
+
+impl Melt {
+    /// The long form of `table`, record by record and column by column.
+    pub fn apply(&self, table: &Table) -> Result<Table, ChartError> {
+        let headers = table.headers();
+        let ids: Vec<usize> = self
+            .id
+            .iter()
+            .map(|col| col.resolve(headers))
+            .collect::<Result<_, _>>()?;
+
+        let mut values: Vec<usize> = Vec::new();
+        if self.columns.is_empty() {
+            values.extend((0..headers.len()).filter(|col| !ids.contains(col)));
+        }
+        for pattern in &self.columns {
+            let found = matches(pattern, headers)?;
+            if found.is_empty() {
+                return Err(ChartError::ColumnNotFound {
+                    column: pattern.to_string(),
+                    available: headers.iter().map(|header| header.trim().to_string()).collect(),
+                });
+            }
+            for col in found {
+                if !values.contains(&col) && !ids.contains(&col) {
+                    values.push(col);
+                }
+            }
+        }
+
+        let mut names: Vec<String> = ids.iter().map(|&col| headers[col].trim().to_string()).collect();
+        names.push(self.variable.clone());
+        names.push(self.value.clone());
+        let mut long = Table::with_headers(names);
+
+        for (row, &line) in table.lines().iter().enumerate() {
+            for &col in &values {
+                long.lines.push(line);
+                for (column, &id) in long.columns.iter_mut().zip(&ids) {
+                    column.push(table.columns[id][row].clone());
+                }
+                long.columns[ids.len()].push(Some(headers[col].trim().to_string()));
+                long.columns[ids.len() + 1].push(table.columns[col][row].clone());
+            }
+        }
+        Ok(long)
+    }
+}
+
+/// Turns a long table wide: one record per `index` value and one column per
+/// `columns` value, holding the `values` cell of their record. For one
+/// record per year and region,
+///
+/// ```toml
+/// [data.pivot]
+/// index = "region"
+/// columns = "year"
+/// values = "value"
+/// ```
+///
+/// gives a `region` column followed by one column per year.
+///
+/// Index values and columns keep the order they first appear in, compared
+/// with surrounding whitespace trimmed. Records repeating an (index, column)
+/// pair are summed when their values share a unit, which the sum keeps
+/// (`£1.50` and `£2` make `3.5 £`); values in different units, or not
+/// numbers, fail the pivot with a [`ChartError::Conflict`] naming both
+/// records. Empty values are ignored and pairs with no record are missing
+/// values. Records too short to hold the index or column are left out.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Pivot {
+    pub index: Column,
+    pub columns: Column,
+    pub values: Column,
+}
+
+impl Pivot {
+    /// The wide form of `table`.
+    pub fn apply(&self, table: &Table) -> Result<Table, ChartError> {
+        let headers = table.headers();
+        let index_col = self.index.resolve(headers)?;
+        let index = &table.columns[index_col];
+        let columns = &table.columns[self.columns.resolve(headers)?];
+        let values = &table.columns[self.values.resolve(headers)?];
+
+        let mut keys: Vec<&str> = Vec::new();
+        let mut names: Vec<&str> = Vec::new();
+        let mut wide = Table::with_headers(vec![headers[index_col].trim().to_string()]);
+        // The line of the record that first filled each (record, column) cell
+        let mut filled: HashMap<(usize, usize), u64> = HashMap::new();
+
+        for (row, &line) in table.lines().iter().enumerate() {
+            let (Some(key), Some(name)) = (index[row].as_deref(), columns[row].as_deref()) else {
+                continue;
+            };
+            let (key, name) = (key.trim(), name.trim());
+            let record = match keys.iter().position(|k| *k == key) {
+                Some(record) => record,
+                None => {
+                    keys.push(key);
+                    wide.lines.push(line);
+                    wide.columns[0].push(Some(key.to_string()));
+                    for column in wide.columns.iter_mut().skip(1) {
+                        column.push(None);
+                    }
+                    keys.len() - 1
+                }
+            };
+            let col = match names.iter().position(|n| *n == name) {
+                Some(col) => col + 1,
+                None => {
+                    names.push(name);
+                    wide.headers.push(name.to_string());
+                    wide.columns.push(vec![None; keys.len()]);
+                    names.len()
+                }
+            };
+
+            let cell = &mut wide.columns[col][record];
+            let value = values[row].as_deref();
+            let blank = |text: Option<&str>| text.is_none_or(|text| text.trim().is_empty());
+            if blank(value) {
+                // An empty value is kept for the loader to report, unless
+                // another record fills the pair
+                if cell.is_none() {
+                    *cell = values[row].clone();
+                }
+                continue;
+            }
+            let Some(first) = filled.get(&(record, col)).copied() else {
+                // A value that is not a number is kept for the loader to report
+                *cell = values[row].clone();
+                filled.insert((record, col), line);
+                continue;
+            };
+            let (text, value) = (cell.as_deref().unwrap_or(""), value.unwrap_or(""));
+            *cell = match (parse_value(text), parse_value(value)) {
+                (Some((sum, unit)), Some((value, other))) if unit == other => Some(match unit {
+                    Some(unit) => format!("{} {}", sum + value, unit),
+                    None => (sum + value).to_string(),
+                }),
+                _ => {
+                    return Err(ChartError::Conflict(format!(
+                        "cannot pivot: lines {} and {} both give a value for ('{}', '{}'), \
+                         and '{}' cannot be added to '{}'",
+                        first, line, key, name, value, text
+                    )))
+                }
+            };
+        }
+        Ok(wide)
+    }
+}
+
+/// Calls `f` with the line and fields of every record the dialect keeps,
+/// holding back no more than the footer rows it skips. Stops at the first
+/// error `f` returns.
+pub(crate) fn for_each_record<R: Read>(
+    path: &str,
+    dialect: &Dialect,
+    rdr: &mut csv::Reader<R>,
+    mut f: impl FnMut(u64, &csv::StringRecord) -> Result<(), ChartError>,
+) -> Result<(), ChartError> {
+    // Positions count from the header row
+    let skipped_lines = dialect.header_row as u64;
+    let mut pending = VecDeque::with_capacity(dialect.skip_footer + 1);
+
+    for result in rdr.records().skip(dialect.skip_rows) {
+        pending.push_back(result.map_err(|err| ChartError::csv(path, err))?);
+        if pending.len() > dialect.skip_footer {
+            if let Some(record) = pending.pop_front() {
+                f(record.position().map_or(0, |pos| pos.line() + skipped_lines), &record)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn table(headers: &[&str], records: &[&[&str]]) -> Table {
+        let mut table = Table::with_headers(headers.iter().map(|header| header.to_string()).collect());
+        for (row, record) in records.iter().enumerate() {
+            table.lines.push(row as u64 + 2);
+            for (column, cell) in table.columns.iter_mut().zip(record.iter()) {
+                column.push(Some(cell.to_string()));
+            }
+        }
+        table
+    }
+
+    fn passengers() -> Melt {
+        Melt {
+            id: vec![Column::from("month")],
+            variable: "year".to_string(),
+            value: "passengers".to_string(),
+            ..Melt::default()
+        }
+    }
+
+    fn by_year() -> Pivot {
+        Pivot {
+            index: Column::from("month"),
+            columns: Column::from("year"),
+            values: Column::from("passengers"),
+        }
+    }
+
+    #[test]
+    fn melts_every_value_column() {
+        let wide = table(&["month", " 2561 ", "2562"], &[&["Jan", "1", "2"], &["Feb", "3", "4"]]);
+        let long = passengers().apply(&wide).unwrap();
+        let expected = table(
+            &["month", "year", "passengers"],
+            &[&["Jan", "2561", "1"], &["Jan", "2562", "2"], &["Feb", "2561", "3"], &["Feb", "2562", "4"]],
+        );
+        assert_eq!(long.headers(), expected.headers());
+        assert_eq!(long.columns, expected.columns);
+        assert_eq!(long.lines(), [2, 2, 3, 3]);
+    }
+
+    #[test]
+    fn never_melts_id_columns() {
+        let wide = table(&["no", "month", "2561", "2562"], &[&["1", "Jan", "1", "2"]]);
+        let melt = Melt {
+            id: vec![Column::from("no"), Column::from("month")],
+            columns: vec![Column::from("*")],
+            ..passengers()
+        };
+        let long = melt.apply(&wide).unwrap();
+        assert_eq!(long.column(&Column::from("year")).unwrap(), [Some("2561".to_string()), Some("2562".to_string())]);
+    }
+
+    #[test]
+    fn pivots_back_what_it_melts() {
+        let wide = table(&["month", "2561", "2562"], &[&["Jan", "1", "2"], &["Feb", "3", "4"]]);
+        let long = passengers().apply(&wide).unwrap();
+        assert_eq!(by_year().apply(&long).unwrap(), wide);
+    }
+
+    #[test]
+    fn pivots_on_trimmed_keys() {
+        let long = table(
+            &["month", "year", "passengers"],
+            &[&["Jan", "2561", "1"], &[" Jan ", " 2561", "2"], &["Feb ", "2562", "4"]],
+        );
+        let wide = by_year().apply(&long).unwrap();
+        assert_eq!(wide.headers(), ["month", "2561", "2562"]);
+        assert_eq!(wide.column(&Column::from("month")).unwrap(), [Some("Jan".to_string()), Some("Feb".to_string())]);
+        assert_eq!(wide.column(&Column::from("2561")).unwrap(), [Some("3".to_string()), None]);
+    }
+
+    #[test]
+    fn sums_repeated_pairs_keeping_their_unit() {
+        let long = table(
+            &["month", "year", "passengers"],
+            &[&["Jan", "2561", "£1.50"], &["Jan", "2561", "£2"], &["Feb", "2561", "5"], &["Feb", "2561", "7"]],
+        );
+        let wide = by_year().apply(&long).unwrap();
+        let cells = wide.column(&Column::from("2561")).unwrap();
+        assert_eq!(cells, [Some("3.5 £".to_string()), Some("12".to_string())]);
+        assert_eq!(parse_value("3.5 £"), Some((3.5, Some("£".to_string()))));
+    }
+
+    #[test]
+    fn ignores_empty_values_of_repeated_pairs() {
+        let long = table(
+            &["month", "year", "passengers"],
+            &[&["Jan", "2561", ""], &["Jan", "2561", "4"], &["Jan", "2561", " "], &["Feb", "2561", ""]],
+        );
+        let wide = by_year().apply(&long).unwrap();
+        let cells = wide.column(&Column::from("2561")).unwrap();
+        assert_eq!(cells, [Some("4".to_string()), Some(String::new())]);
+    }
+
+    #[test]
+    fn rejects_repeated_pairs_that_cannot_be_summed() {
+        for (first, second) in [("5%", "7"), ("£1", "n/a"), ("n/a", "3")] {
+            let long = table(
+                &["month", "year", "passengers"],
+                &[&["Jan", "2561", "1"], &["Feb", "2561", first], &["Feb", "2562", "2"], &["Feb ", "2561", second]],
+            );
+            match by_year().apply(&long) {
+                Err(ChartError::Conflict(message)) => {
+                    assert!(message.contains("lines 3 and 5"), "{}", message);
+                    assert!(message.contains("('Feb', '2561')"), "{}", message);
+                    assert!(message.contains(first) && message.contains(second), "{}", message);
+                }
+                other => panic!("expected a conflict for {} and {}, got {:?}", first, second, other),
+            }
+        }
+    }
+}
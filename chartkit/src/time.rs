@@ -0,0 +1,277 @@
+use crate::error::ChartError;
+use chrono::{Datelike, NaiveDate};
+use serde::Deserialize;
+
+/// Formats tried, in order, when no formats are configured. Formats without
+/// a day (or month) are completed with the first day (or January).
+pub const DEFAULT_DATE_FORMATS: &[&str] = &[
+    "%Y-%m-%d",
+    "%d/%m/%Y",
+    "%b %Y",
+    "%B %Y",
+    "%Y-%m",
+    "%m/%Y",
+    "%Y",
+];
+
+/// Buddhist-era years run 543 years ahead of the Gregorian calendar.
+pub const BE_OFFSET: i32 = 543;
+
+// Years from here on are read as Buddhist era; no Gregorian data is dated
+// this far ahead, and no BE data this far back.
+const BE_MIN_YEAR: i32 = 2400;
+
+// Markers that flag a year as Buddhist era regardless of its value.
+const BE_MARKERS: &[&str] = &["พ.ศ.", "พ.ศ", "B.E.", "BE"];
+
+/// The calendar years are shown in on a time axis. Dates are always held
+/// in the Gregorian calendar; this only affects labels.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Calendar {
+    #[default]
+    Gregorian,
+    /// Thai Buddhist era, e.g. 2563.
+    Buddhist,
+    /// Buddhist era with the Gregorian year in parentheses, e.g. "2563 (2020)".
+    Both,
+}
+
+impl Calendar {
+    /// Formats a Gregorian `year` in this calendar.
+    pub fn format_year(&self, year: i32) -> String {
+        match self {
+            Calendar::Gregorian => year.to_string(),
+            Calendar::Buddhist => (year + BE_OFFSET).to_string(),
+            Calendar::Both => format!("{} ({})", year + BE_OFFSET, year),
+        }
+    }
+}
+
+impl std::str::FromStr for Calendar {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "gregorian" | "ce" => Ok(Calendar::Gregorian),
+            "buddhist" | "be" => Ok(Calendar::Buddhist),
+            "both" => Ok(Calendar::Both),
+            _ => Err(format!("invalid calendar '{}', expected gregorian, buddhist or both", s)),
+        }
+    }
+}
+
+// The position of `token` in `text` where it stands on its own, bounded by
+// whitespace, digits or the ends of the text, so "BE" is found in "BE 2563"
+// and "2563BE" but not in "DECEMBER".
+fn find_token(text: &str, token: &str) -> Option<usize> {
+    let bound = |c: Option<char>| c.is_none_or(|c| c.is_whitespace() || c.is_ascii_digit());
+    text.match_indices(token).map(|(pos, _)| pos).find(|&pos| {
+        bound(text[..pos].chars().next_back()) && bound(text[pos + token.len()..].chars().next())
+    })
+}
+
+/// Rewrites the Buddhist-era years in `text` as Gregorian years.
+///
+/// A four-digit year is Buddhist era when it is 2400 or later, or when the
+/// text carries a BE marker (`BE`, `B.E.`, `พ.ศ.`) as a word of its own,
+/// which is removed.
+/// Converting the text rather than a parsed date keeps leap days valid:
+/// 29/02/2563 is 29/02/2020.
+pub fn to_gregorian(text: &str) -> String {
+    let mut text = text.trim().to_string();
+    let mut marked = false;
+    for marker in BE_MARKERS {
+        if let Some(pos) = find_token(&text, marker) {
+            text.replace_range(pos..pos + marker.len(), "");
+            marked = true;
+            break;
+        }
+    }
+
+    let mut result = String::with_capacity(text.len());
+    let mut digits = String::new();
+    let flush = |digits: &mut String, result: &mut String| {
+        match digits.parse::<i32>() {
+            Ok(year) if digits.len() == 4 && (marked || year >= BE_MIN_YEAR) => {
+                result.push_str(&(year - BE_OFFSET).to_string())
+            }
+            _ => result.push_str(digits),
+        }
+        digits.clear();
+    };
+    for c in text.chars() {
+        if c.is_ascii_digit() {
+            digits.push(c);
+        } else {
+            flush(&mut digits, &mut result);
+            result.push(c);
+        }
+    }
+    flush(&mut digits, &mut result);
+
+    // Collapse the gap a removed marker leaves, e.g. "Jan พ.ศ. 2563"
+    result.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// The spacing of ticks on a time axis.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TimeUnit {
+    Month,
+    Quarter,
+    Year,
+}
+
+impl TimeUnit {
+    /// Picks a unit giving a readable number of ticks between two dates.
+    pub fn for_span(start: NaiveDate, end: NaiveDate) -> TimeUnit {
+        let months = month_index(end) - month_index(start);
+        if months <= 24 {
+            TimeUnit::Month
+        } else if months <= 96 {
+            TimeUnit::Quarter
+        } else {
+            TimeUnit::Year
+        }
+    }
+
+    fn months(&self) -> i32 {
+        match self {
+            TimeUnit::Month => 1,
+            TimeUnit::Quarter => 3,
+            TimeUnit::Year => 12,
+        }
+    }
+}
+
+impl std::str::FromStr for TimeUnit {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "month" => Ok(TimeUnit::Month),
+            "quarter" => Ok(TimeUnit::Quarter),
+            "year" => Ok(TimeUnit::Year),
+            _ => Err(format!("invalid time unit '{}', expected month, quarter or year", s)),
+        }
+    }
+}
+
+// Months since year 0, so consecutive months differ by one.
+fn month_index(date: NaiveDate) -> i32 {
+    date.year() * 12 + date.month0() as i32
+}
+
+fn from_month_index(index: i32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(index.div_euclid(12), index.rem_euclid(12) as u32 + 1, 1).unwrap()
+}
+
+/// Parses `text` with the first matching format, or the default formats when
+/// `formats` is empty. Buddhist-era years are converted to Gregorian first
+/// (see [`to_gregorian`]).
+pub fn parse_date<S: AsRef<str>>(text: &str, formats: &[S]) -> Option<NaiveDate> {
+    let text = to_gregorian(text);
+    let text = text.as_str();
+    let try_format = |format: &str| {
+        NaiveDate::parse_from_str(text, format)
+            .or_else(|_| NaiveDate::parse_from_str(&format!("{} 1", text), &format!("{} %d", format)))
+            .or_else(|_| {
+                NaiveDate::parse_from_str(&format!("{} 1 1", text), &format!("{} %m %d", format))
+            })
+            .ok()
+    };
+
+    if formats.is_empty() {
+        DEFAULT_DATE_FORMATS.iter().find_map(|format| try_format(format))
+    } else {
+        formats.iter().find_map(|format| try_format(format.as_ref()))
+    }
+}
+
+/// Parses every label, reporting the first one no format matches.
+pub fn parse_dates<S: AsRef<str>>(labels: &[String], formats: &[S]) -> Result<Vec<NaiveDate>, ChartError> {
+    labels
+        .iter()
+        .map(|label| parse_date(label, formats).ok_or_else(|| ChartError::parse(label, "a date")))
+        .collect()
+}
+
+/// The position of a date on a continuous time axis, in days.
+pub fn date_position(date: NaiveDate) -> f64 {
+    date.num_days_from_ce() as f64
+}
+
+/// The label of a tick at `date`, with the year shown in `calendar`.
+pub fn tick_label(date: NaiveDate, unit: TimeUnit, calendar: Calendar) -> String {
+    let year = calendar.format_year(date.year());
+    match unit {
+        TimeUnit::Month => format!("{} {}", date.format("%b"), year),
+        TimeUnit::Quarter => format!("Q{} {}", date.month0() / 3 + 1, year),
+        TimeUnit::Year => year,
+    }
+}
+
+/// Tick positions and labels at every month, quarter or year boundary from
+/// `start` to `end` inclusive.
+pub fn date_ticks(
+    start: NaiveDate,
+    end: NaiveDate,
+    unit: TimeUnit,
+    calendar: Calendar,
+) -> Vec<(f64, String)> {
+    let step = unit.months();
+    // First boundary on or after `start`
+    let mut index = month_index(start);
+    index = (index + step - 1).div_euclid(step) * step;
+    if from_month_index(index) < start {
+        index += step;
+    }
+
+    let mut ticks = Vec::new();
+    while from_month_index(index) <= end {
+        let date = from_month_index(index);
+        ticks.push((date_position(date), tick_label(date, unit, calendar)));
+        index += step;
+    }
+    ticks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    #[test]
+    fn converts_marked_years() {
+        assert_eq!(to_gregorian("B.E. 2563"), "2020");
+        assert_eq!(to_gregorian("มกราคม พ.ศ. 2563"), "มกราคม 2020");
+        assert_eq!(to_gregorian("พ.ศ.2563"), "2020");
+        assert_eq!(to_gregorian("Jan 2563 BE"), "Jan 2020");
+        assert_eq!(to_gregorian("2563BE"), "2020");
+    }
+
+    #[test]
+    fn leaves_markers_inside_words_alone() {
+        assert_eq!(to_gregorian("DECEMBER 2020"), "DECEMBER 2020");
+        assert_eq!(to_gregorian("SEPTEMBER 2019"), "SEPTEMBER 2019");
+        assert_eq!(to_gregorian("BEST 2020"), "BEST 2020");
+        assert_eq!(to_gregorian("DECEMBER 2563"), "DECEMBER 2020");
+    }
+
+    #[test]
+    fn parses_uppercase_month_names() {
+        assert_eq!(parse_date::<&str>("DECEMBER 2020", &[]), Some(date(2020, 12, 1)));
+        assert_eq!(parse_date::<&str>("NOVEMBER 2020", &[]), Some(date(2020, 11, 1)));
+        assert_eq!(parse_date::<&str>("DEC 2563", &[]), Some(date(2020, 12, 1)));
+    }
+
+    #[test]
+    fn keeps_buddhist_leap_days() {
+        assert_eq!(parse_date::<&str>("29/02/2563", &[]), Some(date(2020, 2, 29)));
+        assert_eq!(parse_date("B.E. 2563-02-29", &["%Y-%m-%d"]), Some(date(2020, 2, 29)));
+    }
+}
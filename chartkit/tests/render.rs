@@ -0,0 +1,77 @@
+use chartkit::{BarChart, ChartError, GroupedBarChart, HistogramChart, LineChart, Scatter3d, ScatterPlot};
+use std::fs;
+use std::path::PathBuf;
+
+// A path in the temp directory unique to this test run.
+fn output(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("chartkit-render-{}-{}", std::process::id(), name))
+}
+
+// Renders with `draw` and checks a non-empty file was written.
+fn renders(name: &str, draw: impl FnOnce(&str) -> Result<(), ChartError>) {
+    let path = output(name);
+    let result = draw(path.to_str().unwrap());
+    let size = fs::metadata(&path).map(|meta| meta.len());
+    let _ = fs::remove_file(&path);
+
+    result.unwrap();
+    assert!(size.unwrap() > 0, "{} is empty", name);
+}
+
+fn labels(names: &[&str]) -> Vec<String> {
+    names.iter().map(|name| name.to_string()).collect()
+}
+
+#[test]
+fn renders_a_bar_chart() {
+    renders("bar.png", |path| {
+        BarChart::new(labels(&["Bangkok", "Chiang Mai", "Phuket"]), vec![73485.0, 22000.0, 15100.0])
+            .caption("Attendance")
+            .generate_chart(path)
+    });
+}
+
+#[test]
+fn renders_a_grouped_bar_chart() {
+    renders("grouped.png", |path| {
+        GroupedBarChart::new(labels(&["Jan", "Feb"]))
+            .group("2022", vec![1.0, 2.0])
+            .group("2023", vec![3.0, 4.0])
+            .generate_chart(path)
+    });
+}
+
+#[test]
+fn renders_a_line_chart() {
+    renders("line.png", |path| {
+        LineChart::new(labels(&["Jan", "Feb", "Mar"]))
+            .series("North", vec![1.0, 3.0, 2.0])
+            .series("South", vec![2.0, 1.0, 4.0])
+            .generate_chart(path)
+    });
+}
+
+#[test]
+fn renders_a_histogram() {
+    renders("histogram.png", |path| {
+        HistogramChart::new((0..100).map(|i| (i % 17) as f64).collect()).generate_chart(path)
+    });
+}
+
+#[test]
+fn renders_scatter_plots() {
+    renders("scatter.png", |path| {
+        ScatterPlot::new(vec![1.0, 2.0, 3.0], vec![30000.0, 42000.0, 51000.0]).generate_plot(path)
+    });
+    renders("scatter3d.gif", |path| {
+        Scatter3d::new(vec![1.0, 2.0], vec![3.0, 4.0], vec![5.0, 6.0]).generate_plot(path)
+    });
+}
+
+#[test]
+fn rejects_empty_charts() {
+    let path = output("empty.png");
+    let path = path.to_str().unwrap();
+    assert!(matches!(HistogramChart::new(Vec::new()).generate_chart(path), Err(ChartError::EmptyData(_))));
+    assert!(matches!(ScatterPlot::new(Vec::new(), Vec::new()).generate_plot(path), Err(ChartError::EmptyData(_))));
+}